@@ -0,0 +1,105 @@
+// Autosave - flushes the active mind map to disk once edits have settled
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use super::commands::flush_mind_map;
+use super::manager::MindMapManager;
+use super::types::FlushError;
+use crate::state::AppState;
+
+/// Sent when an autosave found the file changed on disk, carries the `FlushError`
+/// Autosave pauses until the conflict is resolved with `resolve_flush_conflict`
+pub const AUTOSAVE_CONFLICT_EVENT: &str = "aiMindMap://mindMap/autosave/conflict";
+
+/// How often the dirty flag is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A conflict autosave is waiting on: the file and what the app knew of it then
+struct PendingConflict {
+  file_name: String,
+  fingerprint: Option<u64>,
+}
+
+impl PendingConflict {
+  /// Resolving records a new disk state (overwrite, reload) or switches files (save as copy)
+  fn is_resolved(&self, manager: &MindMapManager) -> bool {
+    manager.get_current_path() != self.file_name
+      || manager.get_disk_fingerprint(&self.file_name) != self.fingerprint
+  }
+}
+
+/// Start the background autosave task
+/// Flushes the active mind map after it has been idle for the configured
+/// autosave interval (0 disables autosave)
+pub fn start_autosave_task<R: tauri::Runtime + 'static>(app: tauri::AppHandle<R>) {
+  tauri::async_runtime::spawn(async move {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+    // Edit that the last failed flush tried to save; retried after the next edit
+    let mut failed_edit: Option<Instant> = None;
+
+    // Conflict found by the last flush; nothing is written until it is resolved
+    let mut conflict: Option<PendingConflict> = None;
+
+    loop {
+      interval.tick().await;
+
+      let delay_secs = app.state::<AppState>().get_autosave_interval_secs();
+      if delay_secs == 0 {
+        continue;
+      }
+
+      let manager = app.state::<MindMapManager>();
+
+      if conflict.as_ref().is_some_and(|c| !c.is_resolved(&manager)) {
+        continue;
+      }
+      conflict = None;
+
+      // Never-saved maps have no file to flush to
+      if manager.is_saved() || manager.get_current_path().is_empty() {
+        continue;
+      }
+
+      let Some(last_edit) = manager.get_last_edit_at() else {
+        continue;
+      };
+
+      if failed_edit == Some(last_edit) || last_edit.elapsed() < Duration::from_secs(delay_secs) {
+        continue;
+      }
+
+      println!("💾 Autosaving after {}s idle", delay_secs);
+
+      // Writing blocks, keep it off the async runtime's worker threads
+      let flush_app = app.clone();
+      let result = tauri::async_runtime::spawn_blocking(move || {
+        flush_mind_map(flush_app.state::<MindMapManager>(), flush_app.clone())
+      })
+        .await
+        .unwrap_or_else(|e| Err(FlushError::from(format!("Autosave task failed: {}", e))));
+
+      match result {
+        Ok(_) => failed_edit = None,
+        Err(error @ FlushError::Conflict { .. }) => {
+          eprintln!("⚠️  Autosave paused: {}", error);
+
+          if let FlushError::Conflict { file_name, .. } = &error {
+            conflict = Some(PendingConflict {
+              fingerprint: manager.get_disk_fingerprint(file_name),
+              file_name: file_name.clone(),
+            });
+          }
+
+          if let Err(e) = app.emit(AUTOSAVE_CONFLICT_EVENT, &error) {
+            eprintln!("⚠️  Failed to emit autosave conflict event: {}", e);
+          }
+        }
+        Err(e) => {
+          eprintln!("⚠️  Autosave failed: {}", e);
+          failed_edit = Some(last_edit);
+        }
+      }
+    }
+  });
+}
@@ -1,20 +1,66 @@
 // Tauri command handlers for mind map operations
 use crate::active_file::files;
+use crate::state::AppState;
+use crate::vector_index;
 use super::cache::update_cache;
+use super::format_migrations::CURRENT_FORMAT_VERSION;
+use super::history::{self, MindMapVersion};
+use super::library::MindMapSummary;
 use super::manager::MindMapManager;
-use super::persistence::{load_mind_map_from_disk, persist_active_file_state};
-use super::types::{MindMap, SaveState, SavingStatePayload};
+use super::nodes::{MindMapEdge, MindMapNode};
+use super::persistence::{self, load_and_repair_mind_map, load_mind_map_from_disk, persist_active_file_state, StorageBackend, StorageMigrationReport};
+use super::recovery::{self, RecoveryMethod, RecoveryProposal};
+use super::types::{create_empty_mind_map, ConflictResolution, FlushError, MindMap, MindMapLoadError, MindMapUiState, SaveState, SavingStatePayload, Viewport};
+use super::validation::{self, ValidationReport};
 use chrono::Utc;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Emitter, Manager, State};
 
 /// Helper function to emit state updates to the frontend
-fn emit_state_update<R: tauri::Runtime>(app: &AppHandle<R>, mind_map: &MindMap) -> Result<(), String> {
+pub(crate) fn emit_state_update<R: tauri::Runtime>(app: &AppHandle<R>, mind_map: &MindMap) -> Result<(), String> {
   app.emit("aiMindMap://mindMap/update", mind_map)
     .map_err(|e| format!("Failed to emit state update: {}", e))
 }
 
+/// Maps without a real name get `untitled.json`, `untitled_2.json`, ...
+fn is_untitled(name: &str) -> bool {
+  name.trim().is_empty() || name.trim().eq_ignore_ascii_case("untitled")
+}
+
+/// Display name matching an allocated untitled file (`untitled_2.json` -> `Untitled 2`)
+fn untitled_display_name(file_name: &str) -> String {
+  match file_name.strip_prefix("untitled_").and_then(|rest| rest.strip_suffix(".json")) {
+    Some(n) => format!("Untitled {}", n),
+    None => "Untitled".to_string(),
+  }
+}
+
+/// Sanitize a mind map name into a file name that isn't taken in the data directory
+fn allocate_file_name<R: tauri::Runtime>(app: &AppHandle<R>, name: &str) -> Result<String, String> {
+  let storage = persistence::storage(app)?;
+
+  files::unique_file_name(&files::sanitize_file_name(name), |candidate| storage.exists(candidate))
+}
+
+/// Make sure a write or destructive operation only touches a mind map the user chose
+/// Names in the data directory are fine, files elsewhere must have been opened or picked
+fn ensure_known_mind_map<R: tauri::Runtime>(
+  manager: &MindMapManager,
+  app: &AppHandle<R>,
+  file_name: &str
+) -> Result<(), String> {
+  // Relative names can't leave the data directory, `resolve_mind_map_path` rejects them
+  let file_path = files::resolve_mind_map_path(app, file_name)?;
+
+  if !Path::new(file_name).is_absolute() || files::is_in_data_dir(app, &file_path) || manager.is_known_file(file_name) {
+    return Ok(());
+  }
+
+  Err(format!("{} was not opened in the app, refusing to change it", file_name))
+}
+
 /// Helper function to update the window title based on the mind map name
-fn update_window_title<R: tauri::Runtime>(app: &AppHandle<R>, mind_map: &MindMap) -> Result<(), String> {
+pub(crate) fn update_window_title<R: tauri::Runtime>(app: &AppHandle<R>, mind_map: &MindMap) -> Result<(), String> {
   // Get the main window
   let window = app.get_webview_window("main")
     .ok_or_else(|| "Failed to get main window".to_string())?;
@@ -44,12 +90,14 @@ pub fn create_mind_map<R: tauri::Runtime>(
   description: String
 ) -> Result<(), String> {
   let new_mind_map = MindMap {
+    version: CURRENT_FORMAT_VERSION,
     id: 0,
     name,
     description,
     file_name: "".to_string(),
-    nodes: serde_json::json!([]),
-    edges: serde_json::json!([]),
+    nodes: vec![],
+    edges: vec![],
+    ui_state: MindMapUiState::default(),
     created_at: Utc::now().to_rfc3339(),
     updated_at: Utc::now().to_rfc3339(),
   };
@@ -83,10 +131,8 @@ pub fn get_mind_map(
   println!("📤 get_mind_map called:");
   println!("   - Name: {}", mind_map.name);
   println!("   - File: {}", mind_map.file_name);
-  println!("   - Nodes: {} items",
-    mind_map.nodes.as_array().map(|a| a.len()).unwrap_or(0));
-  println!("   - Edges: {} items",
-    mind_map.edges.as_array().map(|a| a.len()).unwrap_or(0));
+  println!("   - Nodes: {} items", mind_map.nodes.len());
+  println!("   - Edges: {} items", mind_map.edges.len());
 
   Ok(mind_map)
 }
@@ -106,6 +152,16 @@ pub fn get_save_state(
   })
 }
 
+/// Set the saved state of a map that was just loaded
+/// A repaired map no longer matches its file and is written back by the next save
+pub(crate) fn mark_loaded(manager: &MindMapManager, repaired: bool) {
+  if repaired {
+    manager.mark_unsaved();
+  } else {
+    manager.mark_saved();
+  }
+}
+
 /// Tauri command to load a mind map and broadcast to all windows
 /// Use this when loading from database or file
 #[tauri::command]
@@ -114,14 +170,20 @@ pub fn load_mind_map<R: tauri::Runtime>(
   app: AppHandle<R>,
   file_name: String
 ) -> Result<(), String> {
-  // Load from disk
-  let mind_map = load_mind_map_from_disk(&app, &file_name)?;
+  // Load from disk (remembering the failure so the file can be recovered)
+  let (mind_map, repaired) = load_and_repair_mind_map(&app, &file_name)
+    .inspect_err(|e| manager.set_load_error(MindMapLoadError { file_name: file_name.clone(), message: e.clone() }))?;
+
+  if manager.get_load_error().is_some_and(|e| e.file_name == file_name) {
+    manager.clear_load_error();
+  }
 
   // Update cache (for quick switching)
   update_cache(&manager, file_name.clone(), mind_map.clone());
 
   // Set as active mind map
   manager.set_active_mind_map(mind_map.clone(), file_name.clone());
+  mark_loaded(&manager, repaired);
 
   // Add to recent files
   manager.add_recent_file(file_name);
@@ -146,22 +208,19 @@ pub fn save_mind_map(
   app: AppHandle,
   mut mind_map: MindMap
 ) -> Result<(), String> {
-  // Get app data directory
-  let app_data_dir = files::build_data_path(&app)
-    .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-  // Create directory if it doesn't exist
-  std::fs::create_dir_all(&app_data_dir)
-    .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-
-  // Build file path - generate filename if not set
+  // Build file path - generate a sanitized, unused filename if not set
+  // (absolute paths are kept, relative names live in the data directory)
   let file_name = if mind_map.file_name.is_empty() {
-    format!("{}.json", mind_map.name.replace(" ", "_"))
+    let file_name = allocate_file_name(&app, &mind_map.name)?;
+    if is_untitled(&mind_map.name) {
+      mind_map.name = untitled_display_name(&file_name);
+    }
+    file_name
   } else {
     mind_map.file_name.clone()
   };
 
-  let file_path = app_data_dir.join(&file_name);
+  ensure_known_mind_map(&manager, &app, &file_name)?;
 
   // Update the mind map's file_name field with the actual filename used
   mind_map.file_name = file_name.clone();
@@ -169,15 +228,10 @@ pub fn save_mind_map(
   // Update the updated_at timestamp
   mind_map.updated_at = Utc::now().to_rfc3339();
 
-  // Serialize the MindMap to a JSON string (with updated file_name)
-  let json_string = serde_json::to_string_pretty(&mind_map)
-    .map_err(|e| format!("Failed to serialize mind map: {}", e))?;
-
-  // Write to file
-  std::fs::write(&file_path, json_string)
-    .map_err(|e| format!("Failed to write file: {}", e))?;
+  // Write it, keep a version snapshot and re-embed changed nodes
+  write_mind_map_file(&app, &file_name, &mind_map)?;
 
-  println!("💾 Mind map saved to: {:?}", file_path);
+  println!("💾 Mind map saved: {}", file_name);
 
   // Update cache (for quick switching)
   update_cache(&manager, file_name.clone(), mind_map.clone());
@@ -201,20 +255,52 @@ pub fn save_mind_map(
   Ok(())
 }
 
+/// Check that a file still has the content the app last read or wrote
+/// Files without a recorded fingerprint or that no longer exist are not conflicts
+fn check_unchanged_on_disk(manager: &MindMapManager, file_name: &str, file_path: &Path) -> Result<(), FlushError> {
+  let Some(known) = manager.get_disk_fingerprint(file_name) else {
+    return Ok(());
+  };
+
+  let Ok(contents) = std::fs::read(file_path) else {
+    return Ok(());
+  };
+
+  if files::content_fingerprint(&contents) != known {
+    return Err(FlushError::Conflict {
+      file_name: file_name.to_string(),
+      message: format!("{} was changed by another program since it was loaded", file_name),
+    });
+  }
+
+  Ok(())
+}
+
 /// Tauri command to flush the current active mind map to disk
+/// Refuses to overwrite a file that changed on disk, see `resolve_flush_conflict`
 #[tauri::command]
 pub fn flush_mind_map<R: tauri::Runtime>(
   manager: State<'_, MindMapManager>,
   app: AppHandle<R>
-) -> Result<(), String> {
-  // Emit saving started event
-  app.emit("aiMindMap://mindMap/saving", SavingStatePayload { is_saving: true })
-    .map_err(|e| format!("Failed to emit saving started event: {}", e))?;
+) -> Result<(), FlushError> {
+  // One flush at a time, otherwise one sees the other's write as an external change
+  let _flush_guard = manager.lock_flush();
 
-  // Get active mind map and path
+  // Get active mind map and path (remembering which edit this state includes)
+  let edit_marker = manager.get_last_edit_at();
   let mind_map = manager.get_active_mind_map();
   let path = manager.get_current_path();
 
+  // Don't overwrite changes made by another program (only files can be edited externally)
+  if !path.is_empty() && app.state::<AppState>().get_storage_backend() == StorageBackend::Json {
+    let file_path = files::resolve_mind_map_path(&app, &path)?;
+    check_unchanged_on_disk(&manager, &path, &file_path)?;
+  }
+
+  // Emit saving started event
+  app.emit("aiMindMap://mindMap/saving", SavingStatePayload { is_saving: true })
+    .map_err(|e| format!("Failed to emit saving started event: {}", e))?;
+
   // If no path set (unsaved new map), skip flushing
   if path.is_empty() || mind_map.file_name.is_empty() {
     println!("⏭️  Skipping flush for unsaved mind map");
@@ -226,29 +312,21 @@ pub fn flush_mind_map<R: tauri::Runtime>(
     return Ok(());
   }
 
-  // Get app data directory
-  let app_data_dir = files::build_data_path(&app)
-    .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+  // Write to the selected storage
+  persistence::save_mind_map_to_disk(&app, &path, &mind_map)?;
 
-  // Create directory if it doesn't exist
-  std::fs::create_dir_all(&app_data_dir)
-    .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+  // Mark as saved (unless it was edited while writing)
+  manager.mark_saved_if_unchanged(edit_marker);
 
-  // Build file path
-  let file_path = app_data_dir.join(&path);
+  println!("💾 Mind map flushed: {}", path);
 
-  // Serialize the MindMap to a JSON string
-  let json_string = serde_json::to_string_pretty(&mind_map)
-    .map_err(|e| format!("Failed to serialize mind map: {}", e))?;
-
-  // Write to file
-  std::fs::write(&file_path, json_string)
-    .map_err(|e| format!("Failed to write file: {}", e))?;
-
-  // Mark as saved
-  manager.mark_saved();
+  // Keep a snapshot so this state can be restored later
+  if let Err(e) = history::record_version(&app, &path, &mind_map) {
+    eprintln!("⚠️  Failed to record mind map version: {}", e);
+  }
 
-  println!("💾 Mind map flushed to disk: {:?}", file_path);
+  // Re-embed changed nodes in the background
+  vector_index::schedule_index_update(&app, &path, &mind_map);
 
   // Emit saving completed event
   app.emit("aiMindMap://mindMap/saving", SavingStatePayload { is_saving: false })
@@ -261,7 +339,7 @@ pub fn flush_mind_map<R: tauri::Runtime>(
 #[tauri::command]
 pub fn update_edges(
   manager: State<'_, MindMapManager>,
-  edges: serde_json::Value
+  edges: Vec<MindMapEdge>
 ) -> Result<(), String> {
   // Update edges in active mind map
   manager.update_edges(edges);
@@ -271,11 +349,81 @@ pub fn update_edges(
   Ok(())
 }
 
+/// Tauri command to check a mind map for structural problems
+/// Checks the open mind map, or the stored copy of `file_name` as it is without repairs
+#[tauri::command]
+pub fn validate_mind_map<R: tauri::Runtime>(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle<R>,
+  file_name: Option<String>
+) -> Result<ValidationReport, String> {
+  let (file_name, mind_map) = match file_name {
+    Some(file_name) => {
+      let mind_map = persistence::read_mind_map_as_stored(&app, &file_name)?;
+      (file_name, mind_map)
+    }
+    None => (manager.get_current_path(), manager.get_active_mind_map()),
+  };
+
+  Ok(ValidationReport {
+    file_name,
+    diagnostics: validation::validate(&mind_map),
+    repaired: vec![],
+  })
+}
+
+/// Tauri command to fix the structural problems of the open mind map that can be fixed safely
+/// The repaired map is marked unsaved and sent to the frontend
+#[tauri::command]
+pub fn repair_mind_map<R: tauri::Runtime>(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle<R>
+) -> Result<ValidationReport, String> {
+  let mut mind_map = manager.get_active_mind_map();
+  let report = validation::repair(&mut mind_map);
+
+  if !report.repaired.is_empty() {
+    manager.update_nodes(mind_map.nodes);
+    manager.update_edges(mind_map.edges);
+    emit_state_update(&app, &manager.get_active_mind_map())?;
+
+    println!("🩺 Repaired {} structural problem(s) in the active mind map", report.repaired.len());
+  }
+
+  Ok(report)
+}
+
+/// Tauri command to update the viewport of the current mind map
+/// Doesn't mark the map as unsaved, the viewport is written with the next save
+#[tauri::command]
+pub fn update_viewport(
+  manager: State<'_, MindMapManager>,
+  viewport: Viewport
+) -> Result<(), String> {
+  manager.update_viewport(viewport);
+
+  Ok(())
+}
+
+/// Tauri command to update the UI state (viewport, layout, collapsed branches) of the current mind map
+/// Like `update_viewport`, this doesn't mark the map as unsaved
+#[tauri::command]
+pub fn update_ui_state(
+  manager: State<'_, MindMapManager>,
+  ui_state: MindMapUiState
+) -> Result<(), String> {
+  manager.update_ui_state(ui_state);
+
+  println!("✅ UI state updated in active mind map");
+
+  Ok(())
+}
+
 /// Tauri command to update current mind map nodes
 #[tauri::command]
 pub fn update_nodes(
   manager: State<'_, MindMapManager>,
-  nodes: serde_json::Value
+  nodes: Vec<MindMapNode>
 ) -> Result<(), String> {
   // Update nodes in active mind map
   manager.update_nodes(nodes);
@@ -285,6 +433,13 @@ pub fn update_nodes(
   Ok(())
 }
 
+/// Tauri command to list the mind maps saved in the data directory
+/// Unreadable files are included with their `error` set
+#[tauri::command]
+pub fn list_mind_maps(app: AppHandle) -> Result<Vec<MindMapSummary>, String> {
+  persistence::storage(&app)?.list()
+}
+
 /// Tauri command to open a file dialog and load the selected mind map
 #[tauri::command]
 pub async fn open_file_dialog<R: tauri::Runtime>(
@@ -318,21 +473,19 @@ pub async fn open_file_dialog<R: tauri::Runtime>(
       let path_buf = path.into_path()
         .map_err(|e| format!("Failed to convert file path: {}", e))?;
 
-      // Extract just the filename from the full path
-      let file_name = path_buf
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| "Invalid file name".to_string())?
-        .to_string();
+      // Files in the data directory keep their short name, others their absolute path
+      let file_name = files::mind_map_key(&app, &path_buf)?;
+      manager.add_picked_file(file_name.clone());
 
       // Load the mind map from disk
-      let mind_map = load_mind_map_from_disk(&app, &file_name)?;
+      let (mind_map, repaired) = load_and_repair_mind_map(&app, &file_name)?;
 
       // Update cache
       update_cache(&manager, file_name.clone(), mind_map.clone());
 
       // Set as active mind map
       manager.set_active_mind_map(mind_map.clone(), file_name.clone());
+      mark_loaded(&manager, repaired);
 
       // Add to recent files
       manager.add_recent_file(file_name);
@@ -357,3 +510,583 @@ pub async fn open_file_dialog<R: tauri::Runtime>(
   }
 }
 
+/// Resolve an optional file name to the active mind map's file
+fn file_name_or_current(manager: &MindMapManager, file_name: Option<String>) -> Result<String, String> {
+  match file_name {
+    Some(name) => Ok(name),
+    None => {
+      let path = manager.get_current_path();
+      if path.is_empty() {
+        return Err("The active mind map has not been saved yet".to_string());
+      }
+      Ok(path)
+    }
+  }
+}
+
+/// Tauri command to list the saved versions of a mind map (newest first)
+/// Defaults to the active mind map when no file name is given
+#[tauri::command]
+pub fn list_mind_map_versions(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle,
+  file_name: Option<String>
+) -> Result<Vec<MindMapVersion>, String> {
+  let file_name = file_name_or_current(&manager, file_name)?;
+
+  history::list_versions(&app, &file_name)
+}
+
+/// Tauri command to load a saved version without changing the active mind map
+#[tauri::command]
+pub fn preview_mind_map_version(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle,
+  file_name: Option<String>,
+  version_id: String
+) -> Result<MindMap, String> {
+  let file_name = file_name_or_current(&manager, file_name)?;
+
+  history::load_version(&app, &file_name, &version_id)
+}
+
+/// Tauri command to make a saved version the active mind map
+/// The restored map is marked unsaved until it is flushed
+#[tauri::command]
+pub fn restore_mind_map_version(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle,
+  file_name: Option<String>,
+  version_id: String
+) -> Result<(), String> {
+  let file_name = file_name_or_current(&manager, file_name)?;
+
+  let mut mind_map = history::load_version(&app, &file_name, &version_id)?;
+  mind_map.file_name = file_name.clone();
+  mind_map.updated_at = Utc::now().to_rfc3339();
+
+  // Set as active mind map
+  manager.set_active_mind_map(mind_map.clone(), file_name.clone());
+  manager.mark_unsaved();
+
+  // Update cache (for quick switching)
+  update_cache(&manager, file_name.clone(), mind_map.clone());
+
+  // Add to recent files
+  manager.add_recent_file(file_name);
+
+  // Persist the updated ActiveFileState to disk
+  let state = manager.get_state();
+  persist_active_file_state(&app, &state)?;
+
+  // Update window title
+  update_window_title(&app, &mind_map)?;
+
+  // Emit to all windows since this is an external change
+  emit_state_update(&app, &mind_map)?;
+
+  println!("⏪ Restored version {} of {}", version_id, mind_map.file_name);
+
+  Ok(())
+}
+
+/// Write a mind map to storage, keep a version snapshot and re-index it
+fn write_mind_map_file<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  file_name: &str,
+  mind_map: &MindMap
+) -> Result<(), String> {
+  persistence::save_mind_map_to_disk(app, file_name, mind_map)?;
+
+  if let Err(e) = history::record_version(app, file_name, mind_map) {
+    eprintln!("⚠️  Failed to record mind map version: {}", e);
+  }
+
+  vector_index::schedule_index_update(app, file_name, mind_map);
+
+  Ok(())
+}
+
+/// Mind map name derived from a file name (`my_map.json` -> `my map`)
+fn name_from_path(path: &Path) -> Option<String> {
+  path.file_stem()
+    .and_then(|s| s.to_str())
+    .map(|stem| stem.replace('_', " "))
+}
+
+/// Show a save dialog starting in the data directory and return the picked path
+pub(crate) fn pick_save_path<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  title: &str,
+  suggested_name: &str
+) -> Result<PathBuf, String> {
+  use tauri_plugin_dialog::DialogExt;
+
+  let default_dir = files::build_data_path(app).unwrap_or_default();
+
+  let file_path = app
+    .dialog()
+    .file()
+    .set_title(title)
+    .add_filter("Mind Map Files", &["json"])
+    .set_directory(default_dir)
+    .set_file_name(suggested_name)
+    .blocking_save_file()
+    .ok_or_else(|| "No file selected".to_string())?;
+
+  let file_path = file_path.into_path()
+    .map_err(|e| format!("Failed to convert file path: {}", e))?;
+
+  // The user chose this path, so commands may write to it
+  app.state::<MindMapManager>().add_picked_file(files::mind_map_key(app, &file_path)?);
+
+  Ok(file_path)
+}
+
+/// Tauri command to save the active mind map to a new file and switch to it
+/// Shows a save dialog when no path is given; returns the new file name
+#[tauri::command]
+pub async fn save_mind_map_as<R: tauri::Runtime>(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle<R>,
+  path: Option<String>
+) -> Result<String, String> {
+  let edit_marker = manager.get_last_edit_at();
+  let mut mind_map = manager.get_active_mind_map();
+
+  let file_name = match path {
+    Some(path) => files::mind_map_key(&app, Path::new(&path))?,
+    None => {
+      let suggested = files::sanitize_file_name(&mind_map.name);
+      files::mind_map_key(&app, &pick_save_path(&app, "Save Mind Map As", &suggested)?)?
+    }
+  };
+
+  ensure_known_mind_map(&manager, &app, &file_name)?;
+
+  mind_map.file_name = file_name.clone();
+  mind_map.updated_at = Utc::now().to_rfc3339();
+
+  write_mind_map_file(&app, &file_name, &mind_map)?;
+
+  println!("💾 Mind map saved as: {}", file_name);
+
+  // Update cache (for quick switching)
+  update_cache(&manager, file_name.clone(), mind_map.clone());
+
+  // Set as active mind map
+  manager.set_active_mind_map(mind_map.clone(), file_name.clone());
+  manager.mark_saved_if_unchanged(edit_marker);
+
+  // Add to recent files
+  manager.add_recent_file(file_name.clone());
+
+  // Persist the updated ActiveFileState to disk
+  let state = manager.get_state();
+  persist_active_file_state(&app, &state)?;
+
+  // Update window title
+  update_window_title(&app, &mind_map)?;
+
+  // Emit to all windows since this is an external change
+  emit_state_update(&app, &mind_map)?;
+
+  Ok(file_name)
+}
+
+/// Tauri command to rename (move) a mind map file
+///
+/// `new_name` is either a display name, which becomes the map's name and is
+/// sanitized into a file name in the file's current directory, or an absolute
+/// path picked in a save dialog. Defaults to the active mind map when no file
+/// name is given. Returns the new file name.
+#[tauri::command]
+pub fn rename_mind_map<R: tauri::Runtime>(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle<R>,
+  file_name: Option<String>,
+  new_name: String
+) -> Result<String, String> {
+  let old_file_name = file_name_or_current(&manager, file_name)?;
+  ensure_known_mind_map(&manager, &app, &old_file_name)?;
+  let old_path = files::resolve_mind_map_path(&app, &old_file_name)?;
+
+  let new_name = new_name.trim();
+
+  let (new_path, name) = if Path::new(new_name).is_absolute() {
+    let mut new_path = PathBuf::from(new_name);
+    if new_path.extension().is_none() {
+      new_path.set_extension("json");
+    }
+
+    let name = name_from_path(&new_path)
+      .ok_or_else(|| format!("Invalid mind map name: {}", new_name))?;
+
+    (new_path, name)
+  } else {
+    let dir = old_path.parent()
+      .ok_or_else(|| format!("Invalid mind map path: {:?}", old_path))?;
+    let name = new_name.strip_suffix(".json").unwrap_or(new_name).to_string();
+
+    (dir.join(files::sanitize_file_name(new_name)), name)
+  };
+
+  // Paths outside the data directory must come from `pick_save_path`
+  let new_file_name = files::mind_map_key(&app, &new_path)?;
+  ensure_known_mind_map(&manager, &app, &new_file_name)?;
+
+  // A new display name can sanitize to the same file, then only the name changes
+  let moved = new_file_name != old_file_name;
+
+  let storage = persistence::storage(&app)?;
+
+  if moved && storage.exists(&new_file_name)? {
+    return Err(format!("A mind map named {} already exists", new_file_name));
+  }
+
+  let mut mind_map = load_mind_map_from_disk(&app, &old_file_name)?;
+  mind_map.name = name.clone();
+  mind_map.file_name = new_file_name.clone();
+
+  // Keep the version history with the file
+  if moved {
+    if let Err(e) = history::move_versions(&app, &old_file_name, &new_file_name) {
+      eprintln!("⚠️  {}", e);
+    }
+  }
+
+  write_mind_map_file(&app, &new_file_name, &mind_map)?;
+
+  if moved {
+    storage.remove(&old_file_name)?;
+
+    vector_index::schedule_index_removal(&app, &old_file_name);
+
+    // Update cache and recent files
+    manager.cache.invalidate(&old_file_name);
+    manager.rename_recent_file(&old_file_name, &new_file_name);
+  }
+
+  println!("✏️  Mind map renamed: {} -> {}", old_file_name, new_file_name);
+
+  update_cache(&manager, new_file_name.clone(), mind_map);
+
+  // The active map keeps any unsaved edits, only its name and file change
+  if manager.get_current_path() == old_file_name {
+    let mut active = manager.get_active_mind_map();
+    active.name = name;
+    active.file_name = new_file_name.clone();
+    manager.set_active_mind_map(active.clone(), new_file_name.clone());
+
+    update_window_title(&app, &active)?;
+    emit_state_update(&app, &active)?;
+  }
+
+  // Persist the updated ActiveFileState to disk
+  let state = manager.get_state();
+  persist_active_file_state(&app, &state)?;
+
+  Ok(new_file_name)
+}
+
+/// Tauri command to copy a mind map to `<name>_copy.json` next to it
+/// Defaults to the active mind map (including unsaved edits); returns the copy's file name
+#[tauri::command]
+pub fn duplicate_mind_map<R: tauri::Runtime>(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle<R>,
+  file_name: Option<String>
+) -> Result<String, String> {
+  let source_file_name = file_name_or_current(&manager, file_name)?;
+  ensure_known_mind_map(&manager, &app, &source_file_name)?;
+  let source_path = files::resolve_mind_map_path(&app, &source_file_name)?;
+
+  let mut mind_map = if manager.get_current_path() == source_file_name {
+    manager.get_active_mind_map()
+  } else {
+    load_mind_map_from_disk(&app, &source_file_name)?
+  };
+
+  let stem = source_path.file_stem()
+    .and_then(|s| s.to_str())
+    .ok_or_else(|| format!("Invalid mind map file name: {}", source_file_name))?;
+
+  // First free name of `<stem>_copy.json`, `<stem>_copy_2.json`, ...
+  let storage = persistence::storage(&app)?;
+  let copy_name = files::unique_file_name(&format!("{}_copy.json", stem), |candidate| {
+    storage.exists(&files::mind_map_key(&app, &source_path.with_file_name(candidate))?)
+  })?;
+
+  let copy_file_name = files::mind_map_key(&app, &source_path.with_file_name(copy_name))?;
+  let now = Utc::now().to_rfc3339();
+
+  mind_map.name = format!("{} (copy)", mind_map.name);
+  mind_map.file_name = copy_file_name.clone();
+  mind_map.created_at = now.clone();
+  mind_map.updated_at = now;
+
+  write_mind_map_file(&app, &copy_file_name, &mind_map)?;
+
+  println!("📑 Mind map duplicated: {} -> {}", source_file_name, copy_file_name);
+
+  update_cache(&manager, copy_file_name.clone(), mind_map);
+
+  Ok(copy_file_name)
+}
+
+/// Tauri command to delete a mind map by moving it to the trash
+/// (`<data dir>/.trash` for files, a soft delete in SQLite)
+/// Deleting the active mind map switches to a new empty one
+#[tauri::command]
+pub fn delete_mind_map<R: tauri::Runtime>(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle<R>,
+  file_name: String
+) -> Result<(), String> {
+  ensure_known_mind_map(&manager, &app, &file_name)?;
+  persistence::storage(&app)?.trash(&file_name)?;
+
+  manager.cache.invalidate(&file_name);
+  manager.remove_recent_file(&file_name);
+  vector_index::schedule_index_removal(&app, &file_name);
+
+  if manager.get_current_path() == file_name {
+    let new_mind_map = create_empty_mind_map();
+
+    // Set as active mind map with empty path (unsaved)
+    manager.set_active_mind_map(new_mind_map.clone(), String::new());
+    manager.mark_saved();
+
+    update_window_title(&app, &new_mind_map)?;
+    emit_state_update(&app, &new_mind_map)?;
+  }
+
+  // Persist the updated ActiveFileState to disk
+  let state = manager.get_state();
+  persist_active_file_state(&app, &state)?;
+
+  Ok(())
+}
+
+/// Tauri command to resolve a conflict reported by `flush_mind_map`
+/// Returns the file name of the active mind map afterwards
+#[tauri::command]
+pub fn resolve_flush_conflict<R: tauri::Runtime>(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle<R>,
+  resolution: ConflictResolution
+) -> Result<String, String> {
+  let file_name = file_name_or_current(&manager, None)?;
+
+  println!("🤝 Resolving conflict on {}: {:?}", file_name, resolution);
+
+  match resolution {
+    ConflictResolution::Overwrite => {
+      // Accept what is on disk as seen so the flush goes through
+      let file_path = files::resolve_mind_map_path(&app, &file_name)?;
+      if let Ok(contents) = std::fs::read(&file_path) {
+        manager.record_disk_state(&file_name, &contents);
+      }
+
+      flush_mind_map(manager, app).map_err(|e| e.to_string())?;
+
+      Ok(file_name)
+    }
+    ConflictResolution::Reload => {
+      load_mind_map(manager, app.clone(), file_name.clone())?;
+
+      Ok(file_name)
+    }
+    ConflictResolution::SaveAsCopy => {
+      // The copy is written from the active map, so it keeps the local edits
+      let copy_file_name = duplicate_mind_map(manager, app.clone(), None)?;
+      load_mind_map(app.state::<MindMapManager>(), app.clone(), copy_file_name.clone())?;
+
+      Ok(copy_file_name)
+    }
+  }
+}
+
+/// Tauri command to get why the last mind map failed to load, if it did
+#[tauri::command]
+pub fn get_load_error(manager: State<'_, MindMapManager>) -> Option<MindMapLoadError> {
+  manager.get_load_error()
+}
+
+/// Tauri command to dismiss the load error without recovering the file
+#[tauri::command]
+pub fn clear_load_error(manager: State<'_, MindMapManager>) {
+  manager.clear_load_error();
+}
+
+/// Tauri command to recover a mind map file that can no longer be loaded
+///
+/// Tries tolerant parsing first and, if that fails and a `model` is given, asks
+/// the model to reconstruct the map. Defaults to the file of the last load
+/// error. The result is returned for review and kept until it is applied with
+/// `apply_mind_map_recovery` or discarded.
+#[tauri::command]
+pub async fn recover_mind_map(
+  app: AppHandle,
+  file_name: Option<String>,
+  model: Option<String>,
+  request_id: Option<String>
+) -> Result<RecoveryProposal, String> {
+  if app.state::<AppState>().get_storage_backend() != StorageBackend::Json {
+    return Err("Recovery is only available for mind maps stored as JSON files".to_string());
+  }
+
+  let file_name = match file_name {
+    Some(file_name) => file_name,
+    None => app.state::<MindMapManager>()
+      .get_load_error()
+      .map(|e| e.file_name)
+      .ok_or_else(|| "No mind map failed to load".to_string())?,
+  };
+
+  ensure_known_mind_map(&app.state::<MindMapManager>(), &app, &file_name)?;
+
+  let text = recovery::read_raw(&app, &file_name)?;
+
+  let tolerant = recovery::tolerant_parse(&text)
+    .ok_or_else(|| format!("{} could not be parsed, even tolerantly", file_name))
+    .and_then(|(document, notes)| {
+      recovery::build_proposal(&file_name, RecoveryMethod::TolerantParse, document, notes)
+    });
+
+  let proposal = match (tolerant, model) {
+    (Ok(proposal), _) => proposal,
+    (Err(e), Some(model)) => {
+      println!("🩹 {}, asking {} to reconstruct it", e, model);
+
+      let (document, notes) = recovery::reconstruct_with_llm(app.clone(), model, &text, request_id).await?;
+      recovery::build_proposal(&file_name, RecoveryMethod::Llm, document, notes)?
+    }
+    (Err(e), None) => return Err(format!("{}. Choose a model to reconstruct it", e)),
+  };
+
+  println!("🩹 Recovery proposal ready for {} ({} node(s))", file_name, proposal.mind_map.nodes.len());
+
+  app.state::<MindMapManager>().set_pending_recovery(Some(proposal.clone()));
+
+  Ok(proposal)
+}
+
+/// Tauri command to replace a corrupted file with its reviewed recovery and open it
+/// The corrupted original is kept next to it; returns the backup's path
+#[tauri::command]
+pub fn apply_mind_map_recovery<R: tauri::Runtime>(
+  manager: State<'_, MindMapManager>,
+  app: AppHandle<R>,
+  file_name: String
+) -> Result<String, String> {
+  let proposal = manager.take_pending_recovery(&file_name)
+    .ok_or_else(|| format!("No recovered version of {} to apply", file_name))?;
+
+  ensure_known_mind_map(&manager, &app, &file_name)?;
+
+  let backup_path = recovery::apply_proposal(&app, &proposal)?;
+
+  load_mind_map(manager, app, file_name)?;
+
+  Ok(backup_path.to_string_lossy().to_string())
+}
+
+/// Tauri command to throw away a recovery proposal without touching the file
+#[tauri::command]
+pub fn discard_mind_map_recovery(manager: State<'_, MindMapManager>) {
+  manager.set_pending_recovery(None);
+}
+
+/// Tauri command to get the autosave idle delay in seconds (0 = disabled)
+#[tauri::command]
+pub fn get_autosave_interval(app: AppHandle) -> u64 {
+  app.state::<AppState>().get_autosave_interval_secs()
+}
+
+/// Tauri command to change the autosave idle delay (0 disables autosave)
+#[tauri::command]
+pub fn set_autosave_interval(app: AppHandle, seconds: u64) {
+  app.state::<AppState>().set_autosave_interval_secs(&app, seconds);
+}
+
+/// Tauri command to get where mind maps are stored
+#[tauri::command]
+pub fn get_storage_backend(app: AppHandle) -> StorageBackend {
+  app.state::<AppState>().get_storage_backend()
+}
+
+/// Tauri command to switch the mind map storage backend
+/// Existing mind maps are not moved, see `migrate_mind_maps_to_sqlite`
+#[tauri::command]
+pub fn set_storage_backend(app: AppHandle, backend: StorageBackend) {
+  app.state::<AppState>().set_storage_backend(&app, backend);
+
+  println!("🗄️  Mind map storage switched to {:?}", backend);
+}
+
+/// Tauri command to copy the JSON mind maps in the data directory into SQLite
+/// Maps already in the database are skipped unless `overwrite` is set
+#[tauri::command]
+pub async fn migrate_mind_maps_to_sqlite(
+  app: AppHandle,
+  overwrite: Option<bool>
+) -> Result<StorageMigrationReport, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    persistence::migrate_json_to_sqlite(&app, overwrite.unwrap_or(false))
+  })
+    .await
+    .map_err(|e| format!("Migration task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// File of its own for each test, in a fresh temp directory
+  fn test_file(contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("flush-conflict-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let file_path = dir.join("map.json");
+    std::fs::write(&file_path, contents).unwrap();
+    file_path
+  }
+
+  #[test]
+  fn unchanged_file_is_not_a_conflict() {
+    let manager = MindMapManager::new();
+    let file_path = test_file("{}");
+    manager.record_disk_state("map.json", b"{}");
+
+    assert!(check_unchanged_on_disk(&manager, "map.json", &file_path).is_ok());
+
+    std::fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+  }
+
+  #[test]
+  fn externally_changed_file_is_a_conflict() {
+    let manager = MindMapManager::new();
+    let file_path = test_file("{\"name\":\"changed\"}");
+    manager.record_disk_state("map.json", b"{}");
+
+    let result = check_unchanged_on_disk(&manager, "map.json", &file_path);
+    assert!(matches!(result, Err(FlushError::Conflict { file_name, .. }) if file_name == "map.json"));
+
+    std::fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+  }
+
+  #[test]
+  fn unknown_or_missing_files_are_not_conflicts() {
+    let manager = MindMapManager::new();
+    let file_path = test_file("{}");
+
+    // Nothing recorded for the file yet
+    assert!(check_unchanged_on_disk(&manager, "map.json", &file_path).is_ok());
+
+    // Removed since it was recorded
+    manager.record_disk_state("map.json", b"{}");
+    std::fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+    assert!(check_unchanged_on_disk(&manager, "map.json", &file_path).is_ok());
+  }
+}
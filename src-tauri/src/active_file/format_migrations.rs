@@ -0,0 +1,211 @@
+// File format migrations - upgrade mind map documents saved by older versions
+//
+// Every saved mind map carries a `version`; files from before it was added are
+// version 1. Loading a document runs the migrations between its version and
+// `CURRENT_FORMAT_VERSION` in order, on the raw JSON so that each step only has
+// to know the shape of the version before it.
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use super::nodes::CHAT_NODE_TYPE;
+
+/// Version written into every saved mind map
+pub const CURRENT_FORMAT_VERSION: u32 = 2;
+
+/// Version of documents saved before the format was versioned
+pub const UNVERSIONED_FORMAT_VERSION: u32 = 1;
+
+/// Sent after a mind map file was upgraded to the current format
+pub const MIGRATED_EVENT: &str = "aiMindMap://mindMap/migrated";
+
+/// One upgrade step from `to_version - 1` to `to_version`
+struct FormatMigration {
+  to_version: u32,
+  description: &'static str,
+  migrate: fn(&mut Value) -> Result<(), String>,
+}
+
+const MIGRATIONS: &[FormatMigration] = &[
+  FormatMigration {
+    to_version: 2,
+    description: "Store chat nodes as `llm-prompt` with the user's message in `content`",
+    migrate: migrate_v1_to_v2,
+  },
+];
+
+/// Migrations run while loading a mind map file
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatMigrationReport {
+  pub file_name: String,
+  pub from_version: u32,
+  pub to_version: u32,
+  /// Descriptions of the migrations that ran, in order
+  pub applied: Vec<String>,
+  /// Copy of the file as it was before the upgrade
+  pub backup_path: String,
+}
+
+/// Format version of a mind map document
+pub(crate) fn document_version(document: &Value) -> Result<u32, String> {
+  match document.get("version") {
+    None | Some(Value::Null) => Ok(UNVERSIONED_FORMAT_VERSION),
+    Some(version) => version.as_u64()
+      .and_then(|v| u32::try_from(v).ok())
+      .ok_or_else(|| format!("Invalid mind map format version: {}", version)),
+  }
+}
+
+/// Whether a document has to be upgraded before it can be used
+/// Fails for documents saved by a newer version of the app
+pub(crate) fn needs_upgrade(document: &Value) -> Result<bool, String> {
+  let version = document_version(document)?;
+
+  if version > CURRENT_FORMAT_VERSION {
+    return Err(format!(
+      "Mind map was saved in format version {}, this version of the app supports up to {}",
+      version, CURRENT_FORMAT_VERSION
+    ));
+  }
+
+  Ok(version < CURRENT_FORMAT_VERSION)
+}
+
+/// Upgrade a document to the current format version
+/// Returns the descriptions of the migrations that ran
+pub(crate) fn upgrade_document(document: &mut Value) -> Result<Vec<String>, String> {
+  if !needs_upgrade(document)? {
+    return Ok(vec![]);
+  }
+
+  let version = document_version(document)?;
+  let mut applied = vec![];
+
+  for migration in MIGRATIONS.iter().filter(|m| m.to_version > version) {
+    (migration.migrate)(document)
+      .map_err(|e| format!("Failed to upgrade mind map to format version {}: {}", migration.to_version, e))?;
+
+    document["version"] = Value::from(migration.to_version);
+    applied.push(migration.description.to_string());
+  }
+
+  Ok(applied)
+}
+
+/// Where the original of an upgraded file is kept (`name.json` -> `name.json.v1.bak`)
+pub(crate) fn backup_path(file_path: &Path, version: u32) -> PathBuf {
+  let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+  name.push(format!(".v{}.bak", version));
+  file_path.with_file_name(name)
+}
+
+// ============================================================================
+// Migrations
+// ============================================================================
+
+/// Version 1 -> 2
+///
+/// Older files stored chat nodes as `llmPrompt` with the user's message in
+/// `data.userMessage`, which the frontend doesn't render. Missing or `null`
+/// node and edge lists become empty lists.
+fn migrate_v1_to_v2(document: &mut Value) -> Result<(), String> {
+  let map = document.as_object_mut()
+    .ok_or_else(|| "Mind map is not a JSON object".to_string())?;
+
+  for key in ["nodes", "edges"] {
+    if map.get(key).is_none_or(|v| v.is_null()) {
+      map.insert(key.to_string(), Value::Array(vec![]));
+    }
+  }
+
+  let Some(nodes) = map.get_mut("nodes").and_then(|n| n.as_array_mut()) else {
+    return Err("`nodes` is not a list".to_string());
+  };
+
+  for node in nodes.iter_mut().filter(|n| n.get("type").and_then(|t| t.as_str()) == Some("llmPrompt")) {
+    node["type"] = Value::from(CHAT_NODE_TYPE);
+
+    let Some(data) = node.get_mut("data").and_then(|d| d.as_object_mut()) else {
+      continue;
+    };
+
+    // Leave nodes that already have content alone rather than drop either message
+    if data.get("content").and_then(|c| c.as_str()).is_some_and(|c| !c.is_empty()) {
+      continue;
+    }
+
+    if let Some(content) = data.get("userMessage").and_then(|m| m.get("content")).cloned() {
+      data.insert("content".to_string(), content);
+      data.remove("userMessage");
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn unversioned_documents_are_version_1() {
+    assert_eq!(document_version(&json!({ "name": "Plan" })).unwrap(), UNVERSIONED_FORMAT_VERSION);
+    assert_eq!(document_version(&json!({ "version": null })).unwrap(), UNVERSIONED_FORMAT_VERSION);
+    assert!(document_version(&json!({ "version": "2" })).is_err());
+  }
+
+  #[test]
+  fn documents_from_newer_versions_are_rejected() {
+    assert!(!needs_upgrade(&json!({ "version": CURRENT_FORMAT_VERSION })).unwrap());
+    assert!(needs_upgrade(&json!({ "version": CURRENT_FORMAT_VERSION + 1 })).is_err());
+  }
+
+  #[test]
+  fn upgrades_v1_chat_nodes() {
+    let mut document = json!({
+      "nodes": [
+        { "id": "a", "type": "llmPrompt", "data": { "userMessage": { "role": "user", "content": "Hi" } } },
+        { "id": "b", "type": "llmPrompt", "data": { "content": "Kept", "userMessage": { "content": "Dropped" } } },
+        { "id": "c", "type": "text-node", "data": { "content": "Text" } }
+      ],
+      "edges": null
+    });
+
+    let applied = upgrade_document(&mut document).unwrap();
+
+    assert_eq!(applied.len(), 1);
+    assert_eq!(document["version"], CURRENT_FORMAT_VERSION);
+    assert_eq!(document["edges"], json!([]));
+
+    assert_eq!(document["nodes"][0]["type"], CHAT_NODE_TYPE);
+    assert_eq!(document["nodes"][0]["data"], json!({ "content": "Hi" }));
+
+    // Existing content wins, the old message is left for the user to see
+    assert_eq!(document["nodes"][1]["data"]["content"], "Kept");
+    assert!(document["nodes"][1]["data"].get("userMessage").is_some());
+
+    assert_eq!(document["nodes"][2]["type"], "text-node");
+  }
+
+  #[test]
+  fn current_documents_are_left_alone() {
+    let mut document = json!({ "version": CURRENT_FORMAT_VERSION, "nodes": [{ "type": "llmPrompt" }] });
+    let before = document.clone();
+
+    assert!(upgrade_document(&mut document).unwrap().is_empty());
+    assert_eq!(document, before);
+  }
+
+  #[test]
+  fn upgrade_fails_for_documents_that_are_not_objects() {
+    assert!(upgrade_document(&mut json!([1, 2])).is_err());
+    assert!(upgrade_document(&mut json!({ "nodes": "none" })).is_err());
+  }
+
+  #[test]
+  fn backups_keep_the_old_version_in_their_name() {
+    assert_eq!(backup_path(Path::new("/data/plan.json"), 1), PathBuf::from("/data/plan.json.v1.bak"));
+  }
+}
@@ -0,0 +1,229 @@
+// Version history - rolling snapshots of every saved mind map
+//
+// Each save writes a snapshot to `<data dir>/.history/<file stem>/<timestamp>.json`
+// (maps in a subdirectory or outside the data dir get a path hash appended to the stem).
+// Snapshots identical to the latest one are skipped, and old ones are pruned by
+// count and age.
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::format_migrations;
+use super::types::MindMap;
+use crate::files;
+
+const HISTORY_DIR_NAME: &str = ".history";
+const VERSION_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// Most snapshots kept per mind map
+const MAX_VERSIONS: usize = 50;
+
+/// Snapshots older than this are removed
+const MAX_VERSION_AGE_DAYS: i64 = 30;
+
+/// Summary of a stored snapshot
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MindMapVersion {
+  pub version_id: String,
+  pub created_at: String,
+  pub name: String,
+  pub node_count: usize,
+  pub edge_count: usize,
+}
+
+/// Name of a mind map's directory in `.history`
+/// Top-level maps use their stem; maps elsewhere can share a stem (`a.json`,
+/// `sub/a.json`), so a hash of the whole path is appended to tell them apart
+fn history_dir_name(file_name: &str) -> Result<String, String> {
+  let path = Path::new(file_name);
+  let stem = path
+    .file_stem()
+    .and_then(|s| s.to_str())
+    .ok_or_else(|| format!("Invalid mind map file name: {}", file_name))?;
+
+  let top_level = !path.is_absolute() && path.parent().is_none_or(|parent| parent.as_os_str().is_empty());
+  if top_level {
+    return Ok(stem.to_string());
+  }
+
+  // FNV-1a, since the directory name has to stay stable across Rust versions
+  let hash = file_name.bytes().fold(0x811c9dc5u32, |hash, byte| {
+    (hash ^ byte as u32).wrapping_mul(0x01000193)
+  });
+
+  Ok(format!("{}-{:08x}", stem, hash))
+}
+
+fn history_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>, file_name: &str) -> Result<PathBuf, String> {
+  Ok(files::build_data_path(app)?.join(HISTORY_DIR_NAME).join(history_dir_name(file_name)?))
+}
+
+/// Version ids are timestamps; reject anything else so ids can't escape the history dir
+fn version_timestamp(version_id: &str) -> Option<DateTime<Utc>> {
+  NaiveDateTime::parse_from_str(version_id, VERSION_TIMESTAMP_FORMAT)
+    .ok()
+    .map(|dt| dt.and_utc())
+}
+
+/// Version ids in the history dir, newest first
+fn version_ids(dir: &Path) -> Vec<String> {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return vec![];
+  };
+
+  let mut ids: Vec<String> = entries
+    .flatten()
+    .filter_map(|entry| {
+      let name = entry.file_name().to_str()?.to_string();
+      let id = name.strip_suffix(".json")?;
+      version_timestamp(id).map(|_| id.to_string())
+    })
+    .collect();
+
+  // The timestamp format sorts lexically
+  ids.sort_by(|a, b| b.cmp(a));
+  ids
+}
+
+fn read_version(dir: &Path, version_id: &str) -> Result<MindMap, String> {
+  let json_string = files::load_text_file(&dir.join(format!("{}.json", version_id)))?;
+
+  let mut document: serde_json::Value = serde_json::from_str(&json_string)
+    .map_err(|e| format!("Failed to deserialize mind map version: {}", e))?;
+
+  // Snapshots are never rewritten, upgrade older ones every time they are read
+  format_migrations::upgrade_document(&mut document)?;
+
+  serde_json::from_value(document)
+    .map_err(|e| format!("Failed to deserialize mind map version: {}", e))
+}
+
+/// Remove versions beyond the count limit or older than the age limit
+fn prune_versions(dir: &Path) {
+  let cutoff = Utc::now() - Duration::days(MAX_VERSION_AGE_DAYS);
+
+  for (i, id) in version_ids(dir).iter().enumerate() {
+    let expired = version_timestamp(id).is_some_and(|ts| ts < cutoff);
+
+    if i >= MAX_VERSIONS || expired {
+      if let Err(e) = std::fs::remove_file(dir.join(format!("{}.json", id))) {
+        eprintln!("⚠️  Failed to remove old mind map version {}: {}", id, e);
+      }
+    }
+  }
+}
+
+/// Store a snapshot of a just-saved mind map
+/// Does nothing when the content matches the latest snapshot
+pub(crate) fn record_version<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  file_name: &str,
+  mind_map: &MindMap
+) -> Result<(), String> {
+  let dir = history_dir(app, file_name)?;
+
+  if let Some(latest) = version_ids(&dir).first() {
+    if let Ok(previous) = read_version(&dir, latest) {
+      if previous.name == mind_map.name && previous.nodes == mind_map.nodes && previous.edges == mind_map.edges {
+        return Ok(());
+      }
+    }
+  }
+
+  std::fs::create_dir_all(&dir)
+    .map_err(|e| format!("Failed to create history directory: {}", e))?;
+
+  let version_id = Utc::now().format(VERSION_TIMESTAMP_FORMAT).to_string();
+
+  let json_string = serde_json::to_string(mind_map)
+    .map_err(|e| format!("Failed to serialize mind map version: {}", e))?;
+
+  files::write_atomic(&dir.join(format!("{}.json", version_id)), json_string)
+    .map_err(|e| format!("Failed to write mind map version: {}", e))?;
+
+  prune_versions(&dir);
+
+  Ok(())
+}
+
+/// List the stored versions of a mind map, newest first
+pub(crate) fn list_versions<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  file_name: &str
+) -> Result<Vec<MindMapVersion>, String> {
+  let dir = history_dir(app, file_name)?;
+
+  Ok(version_ids(&dir)
+    .into_iter()
+    .filter_map(|id| {
+      let mind_map = match read_version(&dir, &id) {
+        Ok(map) => map,
+        Err(e) => {
+          eprintln!("⚠️  Skipping unreadable mind map version {}: {}", id, e);
+          return None;
+        }
+      };
+
+      Some(MindMapVersion {
+        created_at: version_timestamp(&id)?.to_rfc3339(),
+        name: mind_map.name,
+        node_count: mind_map.nodes.len(),
+        edge_count: mind_map.edges.len(),
+        version_id: id,
+      })
+    })
+    .collect())
+}
+
+/// Load a stored version of a mind map
+pub(crate) fn load_version<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  file_name: &str,
+  version_id: &str
+) -> Result<MindMap, String> {
+  if version_timestamp(version_id).is_none() {
+    return Err(format!("Invalid version id: {}", version_id));
+  }
+
+  read_version(&history_dir(app, file_name)?, version_id)
+}
+
+/// Carry the stored versions over when a mind map is renamed or moved
+/// Leaves both directories alone if the new name already has history
+pub(crate) fn move_versions<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  old_file_name: &str,
+  new_file_name: &str
+) -> Result<(), String> {
+  let old_dir = history_dir(app, old_file_name)?;
+  let new_dir = history_dir(app, new_file_name)?;
+
+  if !old_dir.exists() || new_dir.exists() {
+    return Ok(());
+  }
+
+  std::fs::rename(&old_dir, &new_dir)
+    .map_err(|e| format!("Failed to move mind map history: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn top_level_maps_use_their_stem() {
+    assert_eq!(history_dir_name("plan.json").unwrap(), "plan");
+  }
+
+  #[test]
+  fn nested_and_absolute_maps_do_not_share_a_directory() {
+    let names = ["plan.json", "sub/plan.json", "other/plan.json", "/home/me/plan.json"]
+      .map(|file_name| history_dir_name(file_name).unwrap());
+
+    for (i, name) in names.iter().enumerate() {
+      assert!(name.starts_with("plan"));
+      assert!(!names[i + 1..].contains(name), "{} is used twice", name);
+    }
+  }
+}
@@ -0,0 +1,128 @@
+// Mind map library - summaries of every mind map saved in the data directory
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use super::manager::MindMapManager;
+use super::persistence;
+use super::types::MindMap;
+use crate::files;
+
+/// Overview of a saved mind map for the file list
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MindMapSummary {
+  pub file_name: String,
+  pub name: String,
+  pub description: String,
+  pub created_at: String,
+  pub updated_at: String,
+  pub node_count: usize,
+  pub edge_count: usize,
+  pub file_size: u64,
+  /// Last modification time of the file (RFC 3339)
+  pub modified_at: Option<String>,
+  /// Set when the file could not be read or parsed
+  pub error: Option<String>,
+}
+
+impl MindMapSummary {
+  fn from_mind_map(file_name: String, mind_map: &MindMap, file_size: u64, modified_at: Option<String>) -> Self {
+    Self {
+      file_name,
+      name: mind_map.name.clone(),
+      description: mind_map.description.clone(),
+      created_at: mind_map.created_at.clone(),
+      updated_at: mind_map.updated_at.clone(),
+      node_count: mind_map.nodes.len(),
+      edge_count: mind_map.edges.len(),
+      file_size,
+      modified_at,
+      error: None,
+    }
+  }
+
+  fn from_error(file_name: String, file_size: u64, modified_at: Option<String>, error: String) -> Self {
+    Self {
+      name: file_name.trim_end_matches(".json").to_string(),
+      file_name,
+      description: String::new(),
+      created_at: String::new(),
+      updated_at: String::new(),
+      node_count: 0,
+      edge_count: 0,
+      file_size,
+      modified_at,
+      error: Some(error),
+    }
+  }
+}
+
+/// Parse a mind map file, reusing the cached copy if the file hasn't changed since
+fn read_mind_map(
+  manager: &MindMapManager,
+  file_name: &str,
+  path: &Path,
+  modified: Option<SystemTime>
+) -> Result<Arc<MindMap>, String> {
+  if let Some(mind_map) = modified.and_then(|m| manager.get_library_entry(file_name, m)) {
+    return Ok(mind_map);
+  }
+
+  // Older formats are upgraded in memory, the file is only rewritten when it is opened
+  let json_string = files::load_text_file(path)?;
+  let mind_map = Arc::new(persistence::parse_mind_map(&json_string)?);
+
+  if let Some(modified) = modified {
+    manager.cache_library_entry(file_name.to_string(), modified, mind_map.clone());
+  }
+
+  Ok(mind_map)
+}
+
+/// Summarize every `.json` mind map in the data directory, most recently modified first
+/// Files that fail to parse are listed with their error instead of being skipped
+pub(crate) fn list_mind_maps<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  manager: &MindMapManager
+) -> Result<Vec<MindMapSummary>, String> {
+  let data_dir = files::build_data_path(app)?;
+
+  // Nothing saved yet
+  if !data_dir.exists() {
+    return Ok(vec![]);
+  }
+
+  let entries = std::fs::read_dir(&data_dir)
+    .map_err(|e| format!("Failed to read data directory: {}", e))?;
+
+  let mut summaries: Vec<(Option<SystemTime>, MindMapSummary)> = entries
+    .flatten()
+    .filter_map(|entry| {
+      let file_name = entry.file_name().to_str()?.to_string();
+
+      if file_name.starts_with('.') || !file_name.ends_with(".json") {
+        return None;
+      }
+
+      let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+      let modified = metadata.modified().ok();
+      let modified_at = modified.map(|m| chrono::DateTime::<chrono::Utc>::from(m).to_rfc3339());
+
+      let summary = match read_mind_map(manager, &file_name, &entry.path(), modified) {
+        Ok(mind_map) => MindMapSummary::from_mind_map(file_name, &mind_map, metadata.len(), modified_at),
+        Err(e) => {
+          eprintln!("⚠️  Failed to read {} for the library: {}", file_name, e);
+          MindMapSummary::from_error(file_name, metadata.len(), modified_at, e)
+        }
+      };
+
+      Some((modified, summary))
+    })
+    .collect();
+
+  summaries.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+  Ok(summaries.into_iter().map(|(_, summary)| summary).collect())
+}
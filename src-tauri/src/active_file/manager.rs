@@ -1,9 +1,12 @@
 // MindMapManager - manages state and cache for mind maps
-use super::types::{ActiveFileState, MindMap};
+use super::nodes::{FlowEdge, MindMapEdge, MindMapNode};
+use super::recovery::RecoveryProposal;
+use super::types::{ActiveFileState, MindMap, MindMapLoadError, MindMapUiState, Viewport};
 use chrono::{DateTime, Utc};
 use mini_moka::sync::Cache;
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 
 // Manager struct that holds the active mind map and state
 pub struct MindMapManager {
@@ -16,12 +19,33 @@ pub struct MindMapManager {
   // Recent files list
   recent_files: Arc<RwLock<Vec<String>>>,
 
+  // Files outside the data directory the user picked in a dialog this session
+  picked_files: Arc<RwLock<HashSet<String>>>,
+
   // Save state tracking
   is_saved: Arc<RwLock<bool>>,
   last_saved_at: Arc<RwLock<Option<DateTime<Utc>>>>,
 
+  // When the active mind map was last edited (drives the autosave debounce)
+  last_edit_at: Arc<RwLock<Option<Instant>>>,
+
   // Optional: Cache for quick file switching
   pub(crate) cache: Cache<String, Arc<MindMap>>,
+
+  // Content fingerprint of each file as the app last read or wrote it
+  disk_fingerprints: Arc<RwLock<HashMap<String, u64>>>,
+
+  // Held by a flush from its conflict check until the new disk state is recorded
+  flush_lock: Arc<Mutex<()>>,
+
+  // Files parsed for the library listing, keyed by path and modification time
+  library_cache: Cache<(String, SystemTime), Arc<MindMap>>,
+
+  // Why the last mind map failed to load, until it is recovered or dismissed
+  load_error: Arc<RwLock<Option<MindMapLoadError>>>,
+
+  // Recovered mind map waiting for the user's review
+  pending_recovery: Arc<RwLock<Option<RecoveryProposal>>>,
 }
 
 impl MindMapManager {
@@ -37,12 +61,28 @@ impl MindMapManager {
       active_mind_map: Arc::new(RwLock::new(MindMap::default())),
       current_path: Arc::new(RwLock::new(String::new())),
       recent_files: Arc::new(RwLock::new(vec![])),
+      picked_files: Arc::new(RwLock::new(HashSet::new())),
       is_saved: Arc::new(RwLock::new(true)),
       last_saved_at: Arc::new(RwLock::new(None)),
+      last_edit_at: Arc::new(RwLock::new(None)),
+      disk_fingerprints: Arc::new(RwLock::new(HashMap::new())),
+      library_cache: Self::build_library_cache(),
+      load_error: Arc::new(RwLock::new(None)),
+      pending_recovery: Arc::new(RwLock::new(None)),
+      flush_lock: Arc::new(Mutex::new(())),
       cache
     }
   }
 
+  /// Cache of the library listing, large enough for a whole data directory
+  /// Entries of files modified since are never hit again and expire
+  fn build_library_cache() -> Cache<(String, SystemTime), Arc<MindMap>> {
+    Cache::builder()
+      .max_capacity(500)
+      .time_to_idle(Duration::from_secs(5 * 60))
+      .build()
+  }
+
   /// Create manager with loaded mind map (used during initialization)
   pub fn with_loaded_mind_map(state: ActiveFileState, mind_map: MindMap) -> Self {
     let cache = Cache::builder()
@@ -57,8 +97,15 @@ impl MindMapManager {
       active_mind_map: Arc::new(RwLock::new(mind_map)),
       current_path: Arc::new(RwLock::new(path)),
       recent_files: Arc::new(RwLock::new(state.recent_files)),
+      picked_files: Arc::new(RwLock::new(HashSet::new())),
       is_saved: Arc::new(RwLock::new(true)), // Freshly loaded = saved
       last_saved_at: Arc::new(RwLock::new(Some(Utc::now()))),
+      last_edit_at: Arc::new(RwLock::new(None)),
+      disk_fingerprints: Arc::new(RwLock::new(HashMap::new())),
+      library_cache: Self::build_library_cache(),
+      load_error: Arc::new(RwLock::new(None)),
+      pending_recovery: Arc::new(RwLock::new(None)),
+      flush_lock: Arc::new(Mutex::new(())),
       cache
     }
   }
@@ -74,7 +121,7 @@ impl MindMapManager {
   }
 
   /// Update nodes in the active mind map
-  pub fn update_nodes(&self, nodes: serde_json::Value) {
+  pub fn update_nodes(&self, nodes: Vec<MindMapNode>) {
     let mut map = self.active_mind_map.write().unwrap();
     map.nodes = nodes;
     map.updated_at = chrono::Utc::now().to_rfc3339();
@@ -85,7 +132,7 @@ impl MindMapManager {
   }
 
   /// Update edges in the active mind map
-  pub fn update_edges(&self, edges: serde_json::Value) {
+  pub fn update_edges(&self, edges: Vec<MindMapEdge>) {
     let mut map = self.active_mind_map.write().unwrap();
     map.edges = edges;
     map.updated_at = chrono::Utc::now().to_rfc3339();
@@ -95,6 +142,51 @@ impl MindMapManager {
     self.mark_unsaved();
   }
 
+  /// Add a node below `parent_id` in the active mind map, connected by a new edge
+  /// `make_child` gets the parent and its number of children; both are added under
+  /// one lock so concurrent updates can't drop either. Returns the new node's id
+  pub fn append_child_node(
+    &self,
+    parent_id: &str,
+    make_child: impl FnOnce(&MindMapNode, usize) -> MindMapNode
+  ) -> Result<String, String> {
+    let mut map = self.active_mind_map.write().unwrap();
+
+    let parent = map.nodes.iter()
+      .find(|node| node.id() == Some(parent_id))
+      .ok_or_else(|| format!("Node not found: {}", parent_id))?;
+
+    let siblings = map.edges.iter()
+      .filter(|edge| edge.source() == Some(parent_id))
+      .count();
+
+    let child = make_child(parent, siblings);
+    let child_id = child.id()
+      .ok_or_else(|| "New node has no id".to_string())?
+      .to_string();
+
+    map.nodes.push(child);
+    map.edges.push(MindMapEdge::Edge(FlowEdge::new(parent_id, &child_id)));
+    map.updated_at = chrono::Utc::now().to_rfc3339();
+    drop(map); // Release lock before marking unsaved
+
+    // Mark as unsaved
+    self.mark_unsaved();
+
+    Ok(child_id)
+  }
+
+  /// Update the viewport of the active mind map
+  /// UI state is saved with the next save but doesn't mark the map as unsaved
+  pub fn update_viewport(&self, viewport: Viewport) {
+    self.active_mind_map.write().unwrap().ui_state.viewport = viewport;
+  }
+
+  /// Update the layout direction and collapsed branches of the active mind map
+  pub fn update_ui_state(&self, ui_state: MindMapUiState) {
+    self.active_mind_map.write().unwrap().ui_state = ui_state;
+  }
+
   /// Set a new active mind map (used when loading a different file)
   pub fn set_active_mind_map(&self, mind_map: MindMap, path: String) {
     *self.active_mind_map.write().unwrap() = mind_map;
@@ -118,6 +210,88 @@ impl MindMapManager {
     recent.truncate(10);
   }
 
+  /// Remember a file the user picked in a file dialog
+  pub fn add_picked_file(&self, path: String) {
+    self.picked_files.write().unwrap().insert(path);
+  }
+
+  /// Whether the user opened or picked a file: the active one, a recent one or one from a dialog
+  pub fn is_known_file(&self, path: &str) -> bool {
+    *self.current_path.read().unwrap() == path
+      || self.recent_files.read().unwrap().iter().any(|p| p == path)
+      || self.picked_files.read().unwrap().contains(path)
+  }
+
+  /// Remove a file from the recent files list
+  pub fn remove_recent_file(&self, path: &str) {
+    self.recent_files.write().unwrap().retain(|p| p != path);
+  }
+
+  /// Point a recent files entry at a file's new location
+  pub fn rename_recent_file(&self, old_path: &str, new_path: &str) {
+    let mut recent = self.recent_files.write().unwrap();
+    recent.retain(|p| p != new_path);
+    for path in recent.iter_mut().filter(|p| p.as_str() == old_path) {
+      *path = new_path.to_string();
+    }
+  }
+
+  /// Get a mind map parsed for the library if its file was last modified at `modified`
+  pub fn get_library_entry(&self, path: &str, modified: SystemTime) -> Option<Arc<MindMap>> {
+    self.library_cache.get(&(path.to_string(), modified))
+  }
+
+  /// Keep a mind map parsed for the library along with its file modification time
+  pub fn cache_library_entry(&self, path: String, modified: SystemTime, mind_map: Arc<MindMap>) {
+    self.library_cache.insert((path, modified), mind_map);
+  }
+
+  /// Remember the content of a file as it was just read from or written to disk
+  pub fn record_disk_state(&self, path: &str, contents: &[u8]) {
+    let fingerprint = crate::files::content_fingerprint(contents);
+    self.disk_fingerprints.write().unwrap().insert(path.to_string(), fingerprint);
+  }
+
+  /// Get the fingerprint of a file's content as the app last saw it
+  pub fn get_disk_fingerprint(&self, path: &str) -> Option<u64> {
+    self.disk_fingerprints.read().unwrap().get(path).copied()
+  }
+
+  /// Lock out other flushes (autosave, the frontend's save) until the guard is dropped
+  pub fn lock_flush(&self) -> MutexGuard<'_, ()> {
+    self.flush_lock.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  /// Remember why a mind map failed to load
+  pub fn set_load_error(&self, error: MindMapLoadError) {
+    *self.load_error.write().unwrap() = Some(error);
+  }
+
+  /// Get why the last mind map failed to load, if it did
+  pub fn get_load_error(&self) -> Option<MindMapLoadError> {
+    self.load_error.read().unwrap().clone()
+  }
+
+  pub fn clear_load_error(&self) {
+    *self.load_error.write().unwrap() = None;
+  }
+
+  /// Keep a recovered mind map until the user applies or discards it
+  pub fn set_pending_recovery(&self, proposal: Option<RecoveryProposal>) {
+    *self.pending_recovery.write().unwrap() = proposal;
+  }
+
+  /// Take the pending recovery of a file, leaving any other one in place
+  pub fn take_pending_recovery(&self, file_name: &str) -> Option<RecoveryProposal> {
+    let mut pending = self.pending_recovery.write().unwrap();
+
+    if pending.as_ref().is_some_and(|p| p.file_name == file_name) {
+      pending.take()
+    } else {
+      None
+    }
+  }
+
   /// Get ActiveFileState for persistence
   pub fn get_state(&self) -> ActiveFileState {
     let path = self.current_path.read().unwrap();
@@ -132,6 +306,20 @@ impl MindMapManager {
   /// Mark the mind map as unsaved
   pub fn mark_unsaved(&self) {
     *self.is_saved.write().unwrap() = false;
+    *self.last_edit_at.write().unwrap() = Some(Instant::now());
+  }
+
+  /// Get when the active mind map was last edited
+  pub fn get_last_edit_at(&self) -> Option<Instant> {
+    *self.last_edit_at.read().unwrap()
+  }
+
+  /// Mark the mind map as saved unless it was edited after `edit_marker` was read
+  /// Prevents an edit made while a flush is writing from being flagged as saved
+  pub fn mark_saved_if_unchanged(&self, edit_marker: Option<Instant>) {
+    if self.get_last_edit_at() == edit_marker {
+      self.mark_saved();
+    }
   }
 
   /// Mark the mind map as saved
@@ -2,27 +2,51 @@
 //
 // This module is organized into several submodules:
 // - types: Data structures (MindMap, ActiveFileState)
+// - nodes: Typed nodes and edges of a mind map
+// - format_migrations: Upgrades mind map files saved in older format versions
+// - validation: Structural diagnostics and repair of the node graph
+// - recovery: Rebuilds corrupted files by tolerant parsing or with a model
 // - manager: MindMapManager implementation
-// - persistence: Disk I/O operations
+// - persistence: Disk I/O operations and the mind map storage trait
+// - sqlite_storage: SQLite implementation of the storage trait
 // - cache: Cache operations and helpers
+// - history: Rolling version snapshots of saved mind maps
+// - library: Summaries of the saved mind maps for the file list
+// - autosave: Background task that flushes edits once they settle
+// - watcher: Reloads the active mind map when its file is changed externally
 // - commands: Tauri command handlers
 
+mod autosave;
 mod cache;
 pub mod commands;
+mod format_migrations;
+mod history;
+mod library;
 mod manager;
+pub mod nodes;
 mod persistence;
+mod recovery;
+mod sqlite_storage;
 mod types;
+mod validation;
+mod watcher;
 use crate::files;
 use tauri::Manager;
 
 // Re-export public types and functions
 pub use manager::MindMapManager;
+pub use types::MindMap;
 pub use types::{create_empty_mind_map, create_tutorial_mind_map, is_first_time_user};
-pub use persistence::persist_active_file_state;
+pub use persistence::{persist_active_file_state, StorageBackend};
+pub use sqlite_storage::{sql_plugin_migrations, DATABASE_URL};
+pub use autosave::start_autosave_task;
+pub use watcher::start_file_watcher;
+
+pub(crate) use persistence::{load_mind_map_from_disk, storage};
 
 // Public initialization function
-use persistence::{load_active_file_state, load_mind_map_from_disk};
-use types::ActiveFileState;
+use persistence::load_active_file_state;
+use types::{ActiveFileState, MindMapLoadError};
 
 /// Initialize MindMapManager during app setup with eager loading
 /// This ensures the backend always has an active mind map before the frontend starts
@@ -41,16 +65,23 @@ pub fn initialize_mind_map_manager<R: tauri::Runtime>(app: &tauri::App<R>) -> Mi
     }
   };
 
+  // Kept for the frontend so it can offer to recover the file
+  let mut load_error = None;
+
   // Step 2: Try to load the previous mind map
+  let mut repaired = false;
+
   let (active_mind_map, updated_state) = if let Some(path) = &state.current_mind_map_path {
-    match load_mind_map_from_disk(&app_handle, path) {
-      Ok(map) => {
+    match persistence::load_and_repair_mind_map(&app_handle, path) {
+      Ok((map, was_repaired)) => {
         println!("✅ Loaded previous mind map: {}", path);
+        repaired = was_repaired;
         (map, state)
       }
       Err(e) => {
         eprintln!("⚠️  Failed to load {}: {}", path, e);
         println!("📝 Creating default mind map");
+        load_error = Some(MindMapLoadError { file_name: path.clone(), message: e });
         (create_empty_mind_map(), state)
       }
     }
@@ -100,7 +131,18 @@ pub fn initialize_mind_map_manager<R: tauri::Runtime>(app: &tauri::App<R>) -> Mi
     }
   }
 
-  MindMapManager::with_loaded_mind_map(updated_state, active_mind_map)
+  let manager = MindMapManager::with_loaded_mind_map(updated_state, active_mind_map);
+
+  if let Some(error) = load_error {
+    manager.set_load_error(error);
+  }
+
+  // The repairs are written back by the next save
+  if repaired {
+    manager.mark_unsaved();
+  }
+
+  manager
 }
 
 /// Helper to save tutorial to disk
@@ -119,7 +161,7 @@ fn save_tutorial_to_disk<R: tauri::Runtime>(
   let json_string = serde_json::to_string_pretty(tutorial)
     .map_err(|e| format!("Failed to serialize tutorial: {}", e))?;
 
-  std::fs::write(&file_path, json_string)
+  files::write_atomic(&file_path, json_string)
     .map_err(|e| format!("Failed to write tutorial file: {}", e))?;
 
   println!("📚 Tutorial saved to: {:?}", file_path);
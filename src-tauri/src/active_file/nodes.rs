@@ -0,0 +1,327 @@
+// Typed nodes and edges of a mind map
+//
+// Mirrors the React Flow nodes registered by the frontend. Every struct keeps
+// the fields it doesn't know in a flattened `extra` map, and nodes of an
+// unknown type (or that don't match their type's shape) are kept as raw JSON,
+// so saving a mind map never drops data the backend doesn't understand.
+use serde::de::Deserializer;
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub const CHAT_NODE_TYPE: &str = "llm-prompt";
+pub const SUMMARY_NODE_TYPE: &str = "summary-node";
+pub const FILE_NODE_TYPE: &str = "file-node";
+pub const TEXT_NODE_TYPE: &str = "text-node";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct Position {
+  pub x: f64,
+  pub y: f64,
+}
+
+/// A message stored on a chat node
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NodeMessage {
+  pub role: String,
+  pub content: String,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+/// Data shared by every node type
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct BaseNodeData {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub content: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub model: Option<String>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+/// Data of a chat node: the user's message in `content` and the model's answer
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatNodeData {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub locked: Option<bool>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub ai_response: Option<NodeMessage>,
+  #[serde(flatten)]
+  pub base: BaseNodeData,
+}
+
+/// Data of a file node: the file's path, type and extracted text
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FileNodeData {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub locked: Option<bool>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub file: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub mime_type: Option<String>,
+  #[serde(flatten)]
+  pub base: BaseNodeData,
+}
+
+/// A React Flow node with typed data
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FlowNode<D> {
+  pub id: String,
+  #[serde(default)]
+  pub position: Position,
+  pub data: D,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl<D> FlowNode<D> {
+  pub fn new(id: String, position: Position, data: D) -> Self {
+    Self { id, position, data, extra: Map::new() }
+  }
+}
+
+/// A node of a mind map
+#[derive(Debug, Clone, PartialEq)]
+pub enum MindMapNode {
+  Chat(FlowNode<ChatNodeData>),
+  Summary(FlowNode<BaseNodeData>),
+  File(FlowNode<FileNodeData>),
+  Text(FlowNode<BaseNodeData>),
+  /// A node type the backend doesn't know, kept as is
+  Unknown(Value),
+}
+
+impl MindMapNode {
+  pub fn id(&self) -> Option<&str> {
+    match self {
+      MindMapNode::Chat(node) => Some(&node.id),
+      MindMapNode::Summary(node) | MindMapNode::Text(node) => Some(&node.id),
+      MindMapNode::File(node) => Some(&node.id),
+      MindMapNode::Unknown(value) => value.get("id").and_then(|id| id.as_str()),
+    }
+  }
+
+  pub fn node_type(&self) -> &str {
+    match self {
+      MindMapNode::Chat(_) => CHAT_NODE_TYPE,
+      MindMapNode::Summary(_) => SUMMARY_NODE_TYPE,
+      MindMapNode::File(_) => FILE_NODE_TYPE,
+      MindMapNode::Text(_) => TEXT_NODE_TYPE,
+      MindMapNode::Unknown(value) => value.get("type").and_then(|t| t.as_str()).unwrap_or("unknown"),
+    }
+  }
+
+  pub fn position(&self) -> Position {
+    match self {
+      MindMapNode::Chat(node) => node.position,
+      MindMapNode::Summary(node) | MindMapNode::Text(node) => node.position,
+      MindMapNode::File(node) => node.position,
+      MindMapNode::Unknown(value) => Position {
+        x: value.pointer("/position/x").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        y: value.pointer("/position/y").and_then(|v| v.as_f64()).unwrap_or(0.0),
+      },
+    }
+  }
+
+  /// Change the node's id
+  pub fn set_id(&mut self, id: String) {
+    match self {
+      MindMapNode::Chat(node) => node.id = id,
+      MindMapNode::Summary(node) | MindMapNode::Text(node) => node.id = id,
+      MindMapNode::File(node) => node.id = id,
+      MindMapNode::Unknown(value) => {
+        if let Some(object) = value.as_object_mut() {
+          object.insert("id".to_string(), Value::String(id));
+        }
+      }
+    }
+  }
+
+  /// A field of the node's data the typed model doesn't know
+  fn extra_data(&self, key: &str) -> Option<&Value> {
+    match self {
+      MindMapNode::Chat(node) => node.data.base.extra.get(key),
+      MindMapNode::Summary(node) | MindMapNode::Text(node) => node.data.extra.get(key),
+      MindMapNode::File(node) => node.data.base.extra.get(key),
+      MindMapNode::Unknown(value) => value.get("data")?.get(key),
+    }
+  }
+
+  /// Whether the node may have several parents
+  /// Only nodes that stop thread traversal (summary nodes by default) can merge threads
+  pub fn allows_multiple_parents(&self) -> bool {
+    self.extra_data("preventDepthTraversal")
+      .and_then(|v| v.as_bool())
+      .unwrap_or(matches!(self, MindMapNode::Summary(_)))
+  }
+
+  /// The text a node holds, e.g. for search and summaries
+  /// Chat nodes give the user's message followed by the model's answer
+  pub fn text_parts(&self) -> Vec<&str> {
+    let parts: Vec<Option<&str>> = match self {
+      MindMapNode::Chat(node) => vec![
+        node.data.base.content.as_deref(),
+        node.data.ai_response.as_ref().map(|m| m.content.as_str()),
+      ],
+      MindMapNode::Summary(node) | MindMapNode::Text(node) => vec![node.data.content.as_deref()],
+      MindMapNode::File(node) => vec![node.data.base.content.as_deref()],
+      MindMapNode::Unknown(value) => vec![value.pointer("/data/content").and_then(|v| v.as_str())],
+    };
+
+    parts.into_iter()
+      .flatten()
+      .map(str::trim)
+      .filter(|s| !s.is_empty())
+      .collect()
+  }
+}
+
+/// Serialize a typed node with its `type` tag in front
+fn serialize_tagged<S: Serializer, T: Serialize>(serializer: S, node_type: &str, node: &T) -> Result<S::Ok, S::Error> {
+  #[derive(Serialize)]
+  struct Tagged<'a, T> {
+    #[serde(rename = "type")]
+    node_type: &'a str,
+    #[serde(flatten)]
+    node: &'a T,
+  }
+
+  Tagged { node_type, node }.serialize(serializer)
+}
+
+impl Serialize for MindMapNode {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      MindMapNode::Chat(node) => serialize_tagged(serializer, CHAT_NODE_TYPE, node),
+      MindMapNode::Summary(node) => serialize_tagged(serializer, SUMMARY_NODE_TYPE, node),
+      MindMapNode::File(node) => serialize_tagged(serializer, FILE_NODE_TYPE, node),
+      MindMapNode::Text(node) => serialize_tagged(serializer, TEXT_NODE_TYPE, node),
+      MindMapNode::Unknown(value) => value.serialize(serializer),
+    }
+  }
+}
+
+/// Parse a node of a known type, `None` if it doesn't have the expected shape
+fn parse_typed<D: serde::de::DeserializeOwned>(value: &Value) -> Option<FlowNode<D>> {
+  let mut value = value.clone();
+  value.as_object_mut()?.remove("type");
+
+  serde_json::from_value(value).ok()
+}
+
+impl From<Value> for MindMapNode {
+  fn from(value: Value) -> Self {
+    let node = match value.get("type").and_then(|t| t.as_str()) {
+      Some(CHAT_NODE_TYPE) => parse_typed(&value).map(MindMapNode::Chat),
+      Some(SUMMARY_NODE_TYPE) => parse_typed(&value).map(MindMapNode::Summary),
+      Some(FILE_NODE_TYPE) => parse_typed(&value).map(MindMapNode::File),
+      Some(TEXT_NODE_TYPE) => parse_typed(&value).map(MindMapNode::Text),
+      _ => None,
+    };
+
+    node.unwrap_or(MindMapNode::Unknown(value))
+  }
+}
+
+impl<'de> Deserialize<'de> for MindMapNode {
+  fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+    Ok(Value::deserialize(deserializer)?.into())
+  }
+}
+
+/// A React Flow edge between two nodes
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowEdge {
+  pub id: String,
+  pub source: String,
+  pub target: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub source_handle: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub target_handle: Option<String>,
+  #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+  pub edge_type: Option<String>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl FlowEdge {
+  pub fn new(source: &str, target: &str) -> Self {
+    Self {
+      id: format!("xy-edge__{}-{}", source, target),
+      source: source.to_string(),
+      target: target.to_string(),
+      source_handle: None,
+      target_handle: None,
+      edge_type: None,
+      extra: Map::new(),
+    }
+  }
+}
+
+/// An edge of a mind map
+#[derive(Debug, Clone, PartialEq)]
+pub enum MindMapEdge {
+  Edge(FlowEdge),
+  /// An edge without a source or target, kept as is
+  Unknown(Value),
+}
+
+impl MindMapEdge {
+  pub fn id(&self) -> Option<&str> {
+    match self {
+      MindMapEdge::Edge(edge) => Some(&edge.id),
+      MindMapEdge::Unknown(value) => value.get("id").and_then(|id| id.as_str()),
+    }
+  }
+
+  pub fn source(&self) -> Option<&str> {
+    match self {
+      MindMapEdge::Edge(edge) => Some(&edge.source),
+      MindMapEdge::Unknown(value) => value.get("source").and_then(|s| s.as_str()),
+    }
+  }
+
+  pub fn target(&self) -> Option<&str> {
+    match self {
+      MindMapEdge::Edge(edge) => Some(&edge.target),
+      MindMapEdge::Unknown(value) => value.get("target").and_then(|t| t.as_str()),
+    }
+  }
+}
+
+impl Serialize for MindMapEdge {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      MindMapEdge::Edge(edge) => edge.serialize(serializer),
+      MindMapEdge::Unknown(value) => value.serialize(serializer),
+    }
+  }
+}
+
+impl From<Value> for MindMapEdge {
+  fn from(value: Value) -> Self {
+    serde_json::from_value(value.clone())
+      .map(MindMapEdge::Edge)
+      .unwrap_or(MindMapEdge::Unknown(value))
+  }
+}
+
+impl<'de> Deserialize<'de> for MindMapEdge {
+  fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+    Ok(Value::deserialize(deserializer)?.into())
+  }
+}
+
+/// Deserialize a node or edge list, treating `null` as empty
+pub(crate) fn deserialize_list<'de, De, T>(deserializer: De) -> Result<Vec<T>, De::Error>
+where
+  De: Deserializer<'de>,
+  T: Deserialize<'de>,
+{
+  Ok(Option::<Vec<T>>::deserialize(deserializer)?.unwrap_or_default())
+}
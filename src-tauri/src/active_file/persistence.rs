@@ -1,6 +1,20 @@
 // Persistence layer - handles reading and writing to disk
+//
+// Mind maps go through the `MindMapStorage` trait, implemented here for JSON
+// files and in `sqlite_storage` for the SQLite database; the backend is chosen
+// in the app state. Active file state is always a JSON file.
+use super::format_migrations::{self, FormatMigrationReport};
+use super::library::{self, MindMapSummary};
+use super::manager::MindMapManager;
+use super::sqlite_storage::SqliteStorage;
 use super::types::{ActiveFileState, MindMap, SavingStatePayload};
+use super::validation;
 use crate::files;
+use crate::state::AppState;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{Emitter, Manager};
 
 /// Load ActiveFileState from disk
 pub(crate) fn load_active_file_state<R: tauri::Runtime>(
@@ -31,24 +45,352 @@ pub(crate) fn load_active_file_state<R: tauri::Runtime>(
   Ok(state)
 }
 
-/// Load a mind map from disk
+/// Where mind maps are stored
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageBackend {
+  /// One JSON file per mind map in the data directory
+  #[default]
+  Json,
+  /// Mind maps, nodes and edges in the SQLite database shared with the sql plugin
+  Sqlite,
+}
+
+/// Storage for mind maps, keyed by file name
+///
+/// A file name is either a name in the data directory or an absolute path; the
+/// SQLite backend uses it as the mind map's unique key.
+pub trait MindMapStorage {
+  fn load(&self, file_name: &str) -> Result<MindMap, String>;
+  fn save(&self, file_name: &str, mind_map: &MindMap) -> Result<(), String>;
+  fn exists(&self, file_name: &str) -> Result<bool, String>;
+  /// Remove a mind map for good (e.g. after it was renamed)
+  fn remove(&self, file_name: &str) -> Result<(), String>;
+  /// Remove a mind map so that it can still be recovered by hand
+  fn trash(&self, file_name: &str) -> Result<(), String>;
+  fn list(&self) -> Result<Vec<MindMapSummary>, String>;
+}
+
+/// Deleted mind map files are moved here inside the data directory
+const TRASH_DIR_NAME: &str = ".trash";
+
+/// Mind maps stored as JSON files
+pub struct JsonFileStorage<'a, R: tauri::Runtime> {
+  app: &'a tauri::AppHandle<R>,
+}
+
+impl<'a, R: tauri::Runtime> JsonFileStorage<'a, R> {
+  pub fn new(app: &'a tauri::AppHandle<R>) -> Self {
+    Self { app }
+  }
+}
+
+impl<R: tauri::Runtime> JsonFileStorage<'_, R> {
+  /// Upgrade a file saved in an older format version and write it back
+  /// The original is kept next to it as `<name>.v<version>.bak`
+  fn upgrade(
+    &self,
+    file_name: &str,
+    file_path: &Path,
+    json_string: &str,
+    mut document: serde_json::Value
+  ) -> Result<MindMap, String> {
+    let from_version = format_migrations::document_version(&document)?;
+    let backup_path = format_migrations::backup_path(file_path, from_version);
+
+    files::write_atomic(&backup_path, json_string)
+      .map_err(|e| format!("Failed to back up mind map before upgrading: {}", e))?;
+
+    let applied = format_migrations::upgrade_document(&mut document)?;
+
+    let mind_map: MindMap = serde_json::from_value(document)
+      .map_err(|e| format!("Failed to deserialize upgraded mind map: {}", e))?;
+
+    self.save(file_name, &mind_map)?;
+
+    let report = FormatMigrationReport {
+      file_name: file_name.to_string(),
+      from_version,
+      to_version: mind_map.version,
+      applied,
+      backup_path: backup_path.to_string_lossy().to_string(),
+    };
+
+    println!(
+      "⬆️  Upgraded {} from format version {} to {}:",
+      file_name, report.from_version, report.to_version
+    );
+    for description in &report.applied {
+      println!("   - {}", description);
+    }
+
+    if let Err(e) = self.app.emit(format_migrations::MIGRATED_EVENT, &report) {
+      eprintln!("⚠️  Failed to emit mind map migrated event: {}", e);
+    }
+
+    Ok(mind_map)
+  }
+}
+
+/// Move a file, copying it when a plain rename is not possible (e.g. across drives)
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+  if std::fs::rename(from, to).is_ok() {
+    return Ok(());
+  }
+
+  std::fs::copy(from, to)
+    .map_err(|e| format!("Failed to copy file: {}", e))?;
+  std::fs::remove_file(from)
+    .map_err(|e| format!("Failed to remove file: {}", e))
+}
+
+/// Parse the contents of a mind map file, upgrading older formats in memory
+/// Unlike `JsonFileStorage::load` nothing is written back
+pub(crate) fn parse_mind_map(json_string: &str) -> Result<MindMap, String> {
+  let mut document: serde_json::Value = serde_json::from_str(json_string)
+    .map_err(|e| format!("Failed to deserialize mind map: {}", e))?;
+
+  format_migrations::upgrade_document(&mut document)?;
+
+  serde_json::from_value(document)
+    .map_err(|e| format!("Failed to deserialize mind map: {}", e))
+}
+
+impl<R: tauri::Runtime> MindMapStorage for JsonFileStorage<'_, R> {
+  fn load(&self, file_name: &str) -> Result<MindMap, String> {
+    let file_path = files::resolve_mind_map_path(self.app, file_name)?;
+
+    // Read the file using the shared load_text_file function
+    let json_string = files::load_text_file(&file_path)?;
+
+    let mut document: serde_json::Value = serde_json::from_str(&json_string)
+      .map_err(|e| format!("Failed to deserialize mind map: {}", e))?;
+
+    if format_migrations::needs_upgrade(&document)? {
+      return self.upgrade(file_name, &file_path, &json_string, document);
+    }
+
+    let mind_map: MindMap = serde_json::from_value(document.take())
+      .map_err(|e| format!("Failed to deserialize mind map: {}", e))?;
+
+    // Lets the file watcher tell external edits apart (not managed yet during startup)
+    if let Some(manager) = self.app.try_state::<MindMapManager>() {
+      manager.record_disk_state(file_name, json_string.as_bytes());
+    }
+
+    Ok(mind_map)
+  }
+
+  fn save(&self, file_name: &str, mind_map: &MindMap) -> Result<(), String> {
+    let file_path = files::resolve_mind_map_path(self.app, file_name)?;
+
+    // Create directory if it doesn't exist
+    if let Some(dir) = file_path.parent() {
+      std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let json_string = serde_json::to_string_pretty(mind_map)
+      .map_err(|e| format!("Failed to serialize mind map: {}", e))?;
+
+    files::write_atomic(&file_path, &json_string)
+      .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    if let Some(manager) = self.app.try_state::<MindMapManager>() {
+      manager.record_disk_state(file_name, json_string.as_bytes());
+    }
+
+    println!("💾 Mind map written to: {:?}", file_path);
+
+    Ok(())
+  }
+
+  fn exists(&self, file_name: &str) -> Result<bool, String> {
+    let file_path = files::resolve_mind_map_path(self.app, file_name)?;
+
+    file_path.try_exists()
+      .map_err(|e| format!("Failed to check {:?}: {}", file_path, e))
+  }
+
+  fn remove(&self, file_name: &str) -> Result<(), String> {
+    let file_path = files::resolve_mind_map_path(self.app, file_name)?;
+
+    std::fs::remove_file(&file_path)
+      .map_err(|e| format!("Failed to remove file: {}", e))?;
+    let _ = std::fs::remove_file(files::atomic_write::backup_path(&file_path));
+
+    Ok(())
+  }
+
+  fn trash(&self, file_name: &str) -> Result<(), String> {
+    let file_path = files::resolve_mind_map_path(self.app, file_name)?;
+
+    if !file_path.exists() {
+      return Err(format!("Mind map not found: {}", file_name));
+    }
+
+    let trash_dir = files::build_data_path(self.app)?.join(TRASH_DIR_NAME);
+    std::fs::create_dir_all(&trash_dir)
+      .map_err(|e| format!("Failed to create trash directory: {}", e))?;
+
+    // Timestamp prefix so deleting a file with the same name twice keeps both
+    let trash_name = format!(
+      "{}_{}",
+      Utc::now().format("%Y%m%dT%H%M%S%3fZ"),
+      file_path.file_name().and_then(|n| n.to_str()).unwrap_or("mind_map.json")
+    );
+    let trash_path = trash_dir.join(trash_name);
+
+    move_file(&file_path, &trash_path)?;
+    let _ = std::fs::remove_file(files::atomic_write::backup_path(&file_path));
+
+    println!("🗑️  Mind map moved to trash: {:?}", trash_path);
+
+    Ok(())
+  }
+
+  fn list(&self) -> Result<Vec<MindMapSummary>, String> {
+    library::list_mind_maps(self.app, &self.app.state::<MindMapManager>())
+  }
+}
+
+/// Get the storage backend selected in the app state
+pub(crate) fn storage<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>
+) -> Result<Box<dyn MindMapStorage + '_>, String> {
+  match app.state::<AppState>().get_storage_backend() {
+    StorageBackend::Json => Ok(Box::new(JsonFileStorage::new(app))),
+    StorageBackend::Sqlite => Ok(Box::new(SqliteStorage::open(app)?)),
+  }
+}
+
+/// Read a mind map from the selected storage without writing anything back
+/// Files in an older format are upgraded in memory only, and nothing is repaired
+pub(crate) fn read_mind_map_as_stored<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  file_name: &str
+) -> Result<MindMap, String> {
+  match app.state::<AppState>().get_storage_backend() {
+    StorageBackend::Json => {
+      let file_path = files::resolve_mind_map_path(app, file_name)?;
+      parse_mind_map(&files::load_text_file(&file_path)?)
+    }
+    StorageBackend::Sqlite => SqliteStorage::open(app)?.load(file_name),
+  }
+}
+
+/// Load a mind map from the selected storage
+/// `file_name` is either a name in the data directory or an absolute path
 pub(crate) fn load_mind_map_from_disk<R: tauri::Runtime>(
   app: &tauri::AppHandle<R>,
   file_name: &str
 ) -> Result<MindMap, String> {
-  let app_data_dir = files::build_data_path(app)
-    .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+  load_and_repair_mind_map(app, file_name).map(|(mind_map, _)| mind_map)
+}
+
+/// Load a mind map like `load_mind_map_from_disk`, also returning whether it was repaired
+/// A repaired map differs from its stored copy, so a caller that makes it the
+/// active map has to mark it unsaved for the fix to be written back
+pub(crate) fn load_and_repair_mind_map<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  file_name: &str
+) -> Result<(MindMap, bool), String> {
+  let mut mind_map = storage(app)?.load(file_name)?;
 
-  let file_path = app_data_dir.join(file_name);
+  // The stored name may be stale if the file was moved or copied,
+  // saving must go back to the file that was actually loaded
+  mind_map.file_name = file_name.to_string();
 
-  // Read the file using the shared load_text_file function
-  let json_string = files::load_text_file(&file_path)?;
+  // Fix what can be fixed safely; the stored copy is corrected with the next save
+  let report = validation::repair(&mut mind_map);
 
-  // Deserialize from JSON
-  let mind_map: MindMap = serde_json::from_str(&json_string)
-    .map_err(|e| format!("Failed to deserialize mind map: {}", e))?;
+  if !report.is_clean() {
+    println!(
+      "🩺 {}: repaired {} structural problem(s), {} remaining",
+      file_name, report.repaired.len(), report.diagnostics.len()
+    );
+    for diagnostic in &report.diagnostics {
+      println!("   - {}", diagnostic.message);
+    }
+
+    if let Err(e) = app.emit(validation::DIAGNOSTICS_EVENT, &report) {
+      eprintln!("⚠️  Failed to emit mind map diagnostics event: {}", e);
+    }
+  }
+
+  Ok((mind_map, !report.repaired.is_empty()))
+}
+
+/// Write a mind map to the selected storage
+pub(crate) fn save_mind_map_to_disk<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  file_name: &str,
+  mind_map: &MindMap
+) -> Result<(), String> {
+  storage(app)?.save(file_name, mind_map)
+}
+
+/// A mind map that could not be migrated
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageMigrationFailure {
+  pub file_name: String,
+  pub error: String,
+}
 
-  Ok(mind_map)
+/// Outcome of copying the JSON files into SQLite
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageMigrationReport {
+  pub migrated: Vec<String>,
+  /// Already in the database and not overwritten
+  pub skipped: Vec<String>,
+  pub failed: Vec<StorageMigrationFailure>,
+}
+
+/// Copy every mind map file in the data directory into the SQLite database
+/// The JSON files are left as they are; existing rows are only replaced with `overwrite`
+pub(crate) fn migrate_json_to_sqlite<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  overwrite: bool
+) -> Result<StorageMigrationReport, String> {
+  let json = JsonFileStorage::new(app);
+  let sqlite = SqliteStorage::open(app)?;
+  let mut report = StorageMigrationReport::default();
+
+  for summary in json.list()? {
+    let file_name = summary.file_name;
+
+    if let Some(error) = summary.error {
+      report.failed.push(StorageMigrationFailure { file_name, error });
+      continue;
+    }
+
+    if !overwrite && sqlite.exists(&file_name)? {
+      report.skipped.push(file_name);
+      continue;
+    }
+
+    // Older formats are upgraded in memory, the files themselves stay untouched
+    let copied = files::resolve_mind_map_path(app, &file_name)
+      .and_then(|file_path| files::load_text_file(&file_path))
+      .and_then(|json_string| parse_mind_map(&json_string))
+      .and_then(|mind_map| sqlite.save(&file_name, &mind_map));
+
+    match copied {
+      Ok(_) => report.migrated.push(file_name),
+      Err(error) => report.failed.push(StorageMigrationFailure { file_name, error }),
+    }
+  }
+
+  println!(
+    "🗄️  Migrated mind maps to SQLite: {} migrated, {} skipped, {} failed",
+    report.migrated.len(),
+    report.skipped.len(),
+    report.failed.len()
+  );
+
+  Ok(report)
 }
 
 /// Persist ActiveFileState to disk
@@ -56,8 +398,6 @@ pub fn persist_active_file_state<R: tauri::Runtime>(
   app: &tauri::AppHandle<R>,
   state: &ActiveFileState
 ) -> Result<(), String> {
-  use tauri::Emitter;
-
   // Emit saving started event
   app.emit("aiMindMap://mindMap/saving", SavingStatePayload { is_saving: true })
     .map_err(|e| format!("Failed to emit saving started event: {}", e))?;
@@ -78,7 +418,7 @@ pub fn persist_active_file_state<R: tauri::Runtime>(
   let state_file_path = app_data_dir.join("active_file_state.json");
 
   // Write to file
-  std::fs::write(&state_file_path, json_string)
+  files::write_atomic(&state_file_path, json_string)
     .map_err(|e| format!("Failed to write ActiveFileState file: {}", e))?;
 
   println!("ActiveFileState saved to: {:?}", state_file_path);
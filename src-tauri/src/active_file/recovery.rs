@@ -0,0 +1,474 @@
+// Recovery - rebuild mind map files that no longer parse
+//
+// Recovery first tries to parse the file tolerantly (trailing commas, a file cut
+// off mid-write), then asks a model to reconstruct the structure from the raw
+// text. Either way the result is only a proposal: it is kept in the manager for
+// the user to review and replaces the file once applied, after the corrupted
+// original is backed up.
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use super::format_migrations::{self, CURRENT_FORMAT_VERSION};
+use super::persistence::{JsonFileStorage, MindMapStorage};
+use super::types::{create_empty_mind_map, MindMap};
+use super::validation::{self, MindMapDiagnostic};
+use crate::files;
+use crate::ollama::structured::{ollama_chat_json, StructuredOutput};
+use crate::ollama::ChatMessage;
+
+/// How far back from the end of a truncated file to look for a place to cut it
+const MAX_TRUNCATION_ATTEMPTS: usize = 200;
+
+/// Longest part of a corrupted file sent to the model
+const MAX_PROMPT_CHARS: usize = 100_000;
+
+/// Fields every mind map needs; missing ones are filled in from an empty map
+/// The id is not among them, see `build_proposal`
+const REQUIRED_FIELDS: [&str; 5] = ["name", "description", "fileName", "created_at", "updated_at"];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RecoveryMethod {
+  /// Parsed after fixing trailing commas or closing a truncated file
+  TolerantParse,
+  /// Reconstructed by a model from the raw text
+  Llm,
+}
+
+/// A recovered mind map waiting for the user's review
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryProposal {
+  pub file_name: String,
+  pub method: RecoveryMethod,
+  pub mind_map: MindMap,
+  /// What was changed to make the file usable
+  pub notes: Vec<String>,
+  /// Structural problems that could not be repaired
+  pub diagnostics: Vec<MindMapDiagnostic>,
+}
+
+/// Read the raw text of a mind map file
+pub(crate) fn read_raw<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  file_name: &str
+) -> Result<String, String> {
+  let file_path = files::resolve_mind_map_path(app, file_name)?;
+
+  let bytes = std::fs::read(&file_path)
+    .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+
+  // A corrupted file isn't necessarily valid UTF-8 either
+  Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// ============================================================================
+// Tolerant parsing
+// ============================================================================
+
+/// Remove commas directly before a closing bracket
+fn remove_trailing_commas(text: &str) -> String {
+  let chars: Vec<char> = text.chars().collect();
+  let mut result = String::with_capacity(text.len());
+  let mut in_string = false;
+  let mut escaped = false;
+
+  for (i, &c) in chars.iter().enumerate() {
+    if in_string {
+      match c {
+        _ if escaped => escaped = false,
+        '\\' => escaped = true,
+        '"' => in_string = false,
+        _ => {}
+      }
+    } else if c == '"' {
+      in_string = true;
+    } else if c == ',' {
+      let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+      if matches!(next, Some('}') | Some(']')) {
+        continue;
+      }
+    }
+
+    result.push(c);
+  }
+
+  result
+}
+
+/// Close the strings, objects and arrays left open at the end of a truncated file
+/// Returns `None` when the brackets don't match up
+fn close_truncated(text: &str) -> Option<String> {
+  let mut closers = vec![];
+  let mut in_string = false;
+  let mut escaped = false;
+
+  for c in text.chars() {
+    if in_string {
+      match c {
+        _ if escaped => escaped = false,
+        '\\' => escaped = true,
+        '"' => in_string = false,
+        _ => {}
+      }
+      continue;
+    }
+
+    match c {
+      '"' => in_string = true,
+      '{' => closers.push('}'),
+      '[' => closers.push(']'),
+      // The guard pops the matching opener; any other one means the brackets are broken
+      '}' | ']' if closers.pop() != Some(c) => return None,
+      _ => {}
+    }
+  }
+
+  let mut result = text.to_string();
+
+  if in_string {
+    // A file cut off right after a backslash would escape the closing quote
+    if escaped {
+      result.pop();
+    }
+    result.push('"');
+  }
+
+  // A key whose value was cut off
+  if result.trim_end().ends_with(':') {
+    result.push_str("null");
+  }
+
+  result.extend(closers.iter().rev());
+
+  Some(result)
+}
+
+/// Positions of the commas outside of strings, where a truncated file can be cut
+fn cut_points(text: &str) -> Vec<usize> {
+  let mut points = vec![];
+  let mut in_string = false;
+  let mut escaped = false;
+
+  for (i, c) in text.char_indices() {
+    if in_string {
+      match c {
+        _ if escaped => escaped = false,
+        '\\' => escaped = true,
+        '"' => in_string = false,
+        _ => {}
+      }
+    } else if c == '"' {
+      in_string = true;
+    } else if c == ',' {
+      points.push(i);
+    }
+  }
+
+  points
+}
+
+fn parse_object(text: &str) -> Option<Value> {
+  serde_json::from_str::<Value>(text).ok().filter(|value| value.is_object())
+}
+
+/// Parse a damaged mind map file
+/// Returns the document and notes on what had to be fixed
+pub(crate) fn tolerant_parse(text: &str) -> Option<(Value, Vec<String>)> {
+  let text = text.trim_start_matches('\u{feff}').trim();
+
+  if let Some(document) = parse_object(text) {
+    return Some((document, vec![]));
+  }
+
+  let cleaned = remove_trailing_commas(text);
+  if let Some(document) = parse_object(&cleaned) {
+    return Some((document, vec!["Removed trailing commas".to_string()]));
+  }
+
+  if let Some(document) = close_truncated(&cleaned).and_then(|closed| parse_object(&closed)) {
+    return Some((document, vec!["Closed the structure of a truncated file".to_string()]));
+  }
+
+  // Drop the incomplete value at the end, one list item or field at a time
+  for (attempt, cut) in cut_points(&cleaned).into_iter().rev().take(MAX_TRUNCATION_ATTEMPTS).enumerate() {
+    let candidate = close_truncated(&cleaned[..cut]).map(|closed| remove_trailing_commas(&closed));
+
+    if let Some(document) = candidate.and_then(|closed| parse_object(&closed)) {
+      return Some((document, vec![format!(
+        "Closed the structure of a truncated file, dropping {} incomplete item(s) at the end",
+        attempt + 1
+      )]));
+    }
+  }
+
+  None
+}
+
+// ============================================================================
+// Building the proposal
+// ============================================================================
+
+/// Turn a recovered document into a usable mind map
+/// Fills in missing fields, upgrades old formats and repairs the node graph
+pub(crate) fn build_proposal(
+  file_name: &str,
+  method: RecoveryMethod,
+  mut document: Value,
+  mut notes: Vec<String>
+) -> Result<RecoveryProposal, String> {
+  let defaults = serde_json::to_value(create_empty_mind_map())
+    .map_err(|e| format!("Failed to serialize mind map: {}", e))?;
+
+  let object = document.as_object_mut()
+    .ok_or_else(|| "Recovered data is not a JSON object".to_string())?;
+
+  for field in REQUIRED_FIELDS {
+    if object.get(field).is_none_or(|v| v.is_null()) {
+      object.insert(field.to_string(), defaults[field].clone());
+      notes.push(format!("Added the missing field `{}`", field));
+    }
+  }
+
+  // The empty map's id (0) would be shared with every other map recovered this way
+  if !object.get("id").is_some_and(|id| id.is_i64()) {
+    let id = Utc::now().timestamp_millis();
+    object.insert("id".to_string(), Value::from(id));
+    notes.push(format!("The mind map's id was missing or invalid, gave it the new id {}", id));
+  }
+
+  notes.extend(format_migrations::upgrade_document(&mut document)?);
+
+  let mut mind_map: MindMap = serde_json::from_value(document)
+    .map_err(|e| format!("Recovered data is not a valid mind map: {}", e))?;
+  mind_map.file_name = file_name.to_string();
+
+  let report = validation::repair(&mut mind_map);
+  notes.extend(report.repaired.into_iter().map(|diagnostic| format!("Repaired: {}", diagnostic.message)));
+
+  Ok(RecoveryProposal {
+    file_name: file_name.to_string(),
+    method,
+    mind_map,
+    notes,
+    diagnostics: report.diagnostics,
+  })
+}
+
+// ============================================================================
+// Model reconstruction
+// ============================================================================
+
+/// Shape the model has to answer with
+fn reconstruction_schema() -> Value {
+  json!({
+    "type": "object",
+    "required": ["name", "description", "nodes", "edges"],
+    "properties": {
+      "name": { "type": "string" },
+      "description": { "type": "string" },
+      "nodes": {
+        "type": "array",
+        "items": {
+          "type": "object",
+          "required": ["id", "type", "position", "data"],
+          "properties": {
+            "id": { "type": "string" },
+            "type": { "type": "string" },
+            "position": {
+              "type": "object",
+              "required": ["x", "y"],
+              "properties": {
+                "x": { "type": "number" },
+                "y": { "type": "number" }
+              }
+            },
+            "data": { "type": "object" }
+          }
+        }
+      },
+      "edges": {
+        "type": "array",
+        "items": {
+          "type": "object",
+          "required": ["id", "source", "target"],
+          "properties": {
+            "id": { "type": "string" },
+            "source": { "type": "string" },
+            "target": { "type": "string" }
+          }
+        }
+      }
+    }
+  })
+}
+
+fn reconstruction_prompt(text: &str) -> String {
+  let excerpt = match text.char_indices().nth(MAX_PROMPT_CHARS) {
+    Some((end, _)) => &text[..end],
+    None => text,
+  };
+
+  format!(
+    "This mind map file is corrupted and can no longer be parsed as JSON. \
+     Reconstruct it as valid JSON with the mind map's name, description, nodes and edges.\n\
+     Nodes have an id, a type (\"llm-prompt\", \"summary-node\", \"file-node\" or \"text-node\"), \
+     a position {{ x, y }} and a data object. Keep every node's data, including `content` and `aiResponse`, \
+     as close to the original as possible. Edges have an id, a source node id and a target node id.\n\
+     Reply with only the JSON.\n\n\
+     Corrupted file:\n{}",
+    excerpt
+  )
+}
+
+/// Ask a model to reconstruct a mind map from the raw text of a corrupted file
+pub(crate) async fn reconstruct_with_llm(
+  app: tauri::AppHandle,
+  model: String,
+  text: &str,
+  request_id: Option<String>
+) -> Result<(Value, Vec<String>), String> {
+  let messages = vec![ChatMessage {
+    role: "user".to_string(),
+    content: reconstruction_prompt(text),
+    ..Default::default()
+  }];
+
+  let output = StructuredOutput { schema: reconstruction_schema(), max_retries: 2 };
+
+  let mut document = ollama_chat_json(app, model.clone(), messages, output, None, request_id).await?;
+
+  // The model is asked for the current node shapes
+  if let Some(object) = document.as_object_mut() {
+    object.insert("version".to_string(), Value::from(CURRENT_FORMAT_VERSION));
+  }
+
+  let mut notes = vec![format!("Reconstructed by {}, review the content before applying", model)];
+  if text.chars().count() > MAX_PROMPT_CHARS {
+    notes.push(format!("Only the first {} characters of the file were sent to the model", MAX_PROMPT_CHARS));
+  }
+
+  Ok((document, notes))
+}
+
+// ============================================================================
+// Applying a proposal
+// ============================================================================
+
+/// Where the corrupted original is kept (`name.json` -> `name.json.corrupt-<timestamp>.bak`)
+fn corrupt_backup_path(file_path: &std::path::Path) -> PathBuf {
+  let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+  name.push(format!(".corrupt-{}.bak", Utc::now().format("%Y%m%dT%H%M%S")));
+  file_path.with_file_name(name)
+}
+
+/// Back up the corrupted file and replace it with the recovered mind map
+/// Returns the path of the backup
+pub(crate) fn apply_proposal<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  proposal: &RecoveryProposal
+) -> Result<PathBuf, String> {
+  let file_path = files::resolve_mind_map_path(app, &proposal.file_name)?;
+  let backup_path = corrupt_backup_path(&file_path);
+
+  if file_path.exists() {
+    let contents = std::fs::read(&file_path)
+      .map_err(|e| format!("Failed to read corrupted mind map: {}", e))?;
+
+    files::write_atomic(&backup_path, contents)
+      .map_err(|e| format!("Failed to back up corrupted mind map: {}", e))?;
+  }
+
+  JsonFileStorage::new(app).save(&proposal.file_name, &proposal.mind_map)?;
+
+  println!("🩹 Recovered mind map written to {:?} (original kept at {:?})", file_path, backup_path);
+
+  Ok(backup_path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn removes_trailing_commas_outside_of_strings() {
+    assert_eq!(remove_trailing_commas(r#"{"a": [1, 2, ], "b": ",}", }"#), r#"{"a": [1, 2 ], "b": ",}" }"#);
+  }
+
+  #[test]
+  fn closes_a_file_truncated_inside_a_string() {
+    assert_eq!(close_truncated(r#"{"nodes": [{"id": "a", "data": {"content": "Hel"#).as_deref(), Some(r#"{"nodes": [{"id": "a", "data": {"content": "Hel"}}]}"#));
+  }
+
+  #[test]
+  fn closes_a_file_truncated_after_a_key() {
+    assert_eq!(close_truncated(r#"{"name": "Plan", "nodes":"#).as_deref(), Some(r#"{"name": "Plan", "nodes":null}"#));
+  }
+
+  #[test]
+  fn closes_a_file_truncated_after_an_escape() {
+    // Escaped quotes don't end the string, and the dangling backslash is dropped
+    let truncated = "{\"content\": \"say \\\"hi\\\", \\";
+
+    assert_eq!(close_truncated(truncated).as_deref(), Some("{\"content\": \"say \\\"hi\\\", \"}"));
+    assert_eq!(tolerant_parse(truncated).unwrap().0["content"], "say \"hi\", ");
+  }
+
+  #[test]
+  fn cut_points_skip_escaped_quotes() {
+    assert_eq!(cut_points(r#"{"a": "x\",y", "b": 1}"#), vec![13]);
+  }
+
+  #[test]
+  fn rejects_mismatched_brackets() {
+    assert_eq!(close_truncated(r#"{"a": [1}"#), None);
+  }
+
+  #[test]
+  fn cut_points_skip_commas_in_strings() {
+    assert_eq!(cut_points(r#"[1, "a,b", 2]"#), vec![2, 9]);
+  }
+
+  #[test]
+  fn parses_valid_documents_without_notes() {
+    let (document, notes) = tolerant_parse("\u{feff}{\"name\": \"Plan\"}").unwrap();
+
+    assert_eq!(document["name"], "Plan");
+    assert!(notes.is_empty());
+  }
+
+  #[test]
+  fn parses_documents_with_trailing_commas() {
+    let (document, notes) = tolerant_parse(r#"{"nodes": [{"id": "a"},], }"#).unwrap();
+
+    assert_eq!(document["nodes"][0]["id"], "a");
+    assert_eq!(notes, vec!["Removed trailing commas"]);
+  }
+
+  #[test]
+  fn drops_the_incomplete_item_of_a_truncated_file() {
+    let (document, notes) = tolerant_parse(r#"{"nodes": [{"id": "a"}, {"id": "b", "dat"#).unwrap();
+
+    assert_eq!(document["nodes"][0]["id"], "a");
+    assert_eq!(document["nodes"][1]["id"], "b");
+    assert_eq!(notes.len(), 1);
+  }
+
+  #[test]
+  fn gives_up_on_garbage() {
+    assert!(tolerant_parse("not a mind map").is_none());
+    assert!(tolerant_parse("[1, 2, 3]").is_none());
+  }
+
+  #[test]
+  fn proposal_gets_a_fresh_id_and_says_so() {
+    let document = json!({ "name": "Plan", "nodes": [], "edges": [] });
+    let proposal = build_proposal("plan.json", RecoveryMethod::TolerantParse, document, vec![]).unwrap();
+
+    assert_ne!(proposal.mind_map.id, 0);
+    assert!(proposal.notes.iter().any(|note| note.contains("id")));
+    assert_eq!(proposal.mind_map.file_name, "plan.json");
+  }
+}
@@ -0,0 +1,433 @@
+// SQLite storage - mind maps, nodes and edges in the database of the sql plugin
+//
+// The database lives where `tauri_plugin_sql` resolves `sqlite:mind_maps.db`
+// (the app config directory) so the frontend can query it through the plugin.
+// The schema is applied here and registered as a plugin migration as well.
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::Row;
+use std::future::Future;
+use std::sync::{mpsc, OnceLock};
+use tauri::Manager;
+use tokio::runtime::Runtime;
+
+use super::format_migrations::CURRENT_FORMAT_VERSION;
+use super::library::MindMapSummary;
+use super::persistence::MindMapStorage;
+use super::types::{MindMap, MindMapUiState};
+
+/// Connection string used with the sql plugin
+pub const DATABASE_URL: &str = "sqlite:mind_maps.db";
+
+const DATABASE_FILE_NAME: &str = "mind_maps.db";
+
+/// Prefix given to the file name of trashed mind maps so the name can be reused
+const TRASH_PREFIX: &str = ".trash/";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS mind_maps (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  file_name TEXT NOT NULL UNIQUE,
+  name TEXT NOT NULL,
+  description TEXT NOT NULL DEFAULT '',
+  created_at TEXT NOT NULL,
+  updated_at TEXT NOT NULL,
+  deleted_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS mind_map_nodes (
+  mind_map_id INTEGER NOT NULL REFERENCES mind_maps(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  node_id TEXT NOT NULL,
+  node_type TEXT,
+  data TEXT NOT NULL,
+  PRIMARY KEY (mind_map_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS mind_map_edges (
+  mind_map_id INTEGER NOT NULL REFERENCES mind_maps(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  edge_id TEXT NOT NULL,
+  source TEXT,
+  target TEXT,
+  data TEXT NOT NULL,
+  PRIMARY KEY (mind_map_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_mind_map_nodes_node_id ON mind_map_nodes(node_id);
+CREATE INDEX IF NOT EXISTS idx_mind_map_edges_source ON mind_map_edges(source);
+CREATE INDEX IF NOT EXISTS idx_mind_map_edges_target ON mind_map_edges(target);
+";
+
+/// Added after the first schema; a separate table keeps the migration idempotent
+const UI_STATE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS mind_map_ui_state (
+  mind_map_id INTEGER PRIMARY KEY REFERENCES mind_maps(id) ON DELETE CASCADE,
+  data TEXT NOT NULL
+);
+";
+
+/// Migrations for the sql plugin, so the frontend sees the same schema
+pub fn sql_plugin_migrations() -> Vec<tauri_plugin_sql::Migration> {
+  vec![
+    tauri_plugin_sql::Migration {
+      version: 1,
+      description: "create_mind_map_tables",
+      sql: SCHEMA,
+      kind: tauri_plugin_sql::MigrationKind::Up,
+    },
+    tauri_plugin_sql::Migration {
+      version: 2,
+      description: "create_mind_map_ui_state_table",
+      sql: UI_STATE_SCHEMA,
+      kind: tauri_plugin_sql::MigrationKind::Up,
+    },
+  ]
+}
+
+/// Connection pool, opened the first time the SQLite backend is used
+struct SqlitePoolState(SqlitePool);
+
+/// Runtime the database futures run on, separate from the app's async runtime
+fn database_runtime() -> Result<&'static Runtime, String> {
+  static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+  if let Some(runtime) = RUNTIME.get() {
+    return Ok(runtime);
+  }
+
+  let runtime = tokio::runtime::Builder::new_multi_thread()
+    .worker_threads(1)
+    .thread_name("mind-map-db")
+    .enable_all()
+    .build()
+    .map_err(|e| format!("Failed to start database runtime: {}", e))?;
+
+  // Another thread may have started one first, use whichever was stored
+  Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// Run a database future to completion from synchronous code
+///
+/// The future runs on the database runtime and the caller waits for its result,
+/// which also works when called from inside an async task (e.g. autosave), where
+/// blocking on the app's runtime directly would panic.
+fn block_on<T, F>(future: F) -> Result<T, String>
+where
+  T: Send + 'static,
+  F: Future<Output = Result<T, String>> + Send + 'static,
+{
+  let (sender, receiver) = mpsc::channel();
+
+  database_runtime()?.spawn(async move {
+    let _ = sender.send(future.await);
+  });
+
+  receiver.recv().map_err(|_| "Database task panicked".to_string())?
+}
+
+fn db_error(e: sqlx::Error) -> String {
+  format!("Database error: {}", e)
+}
+
+fn to_json<T: Serialize>(item: &T) -> Result<String, String> {
+  serde_json::to_string(item).map_err(|e| format!("Failed to serialize stored item: {}", e))
+}
+
+/// Parse the `data` column of node or edge rows
+fn parse_rows<T: DeserializeOwned>(rows: Vec<SqliteRow>) -> Result<Vec<T>, String> {
+  rows.iter()
+    .map(|row| {
+      serde_json::from_str(row.get::<&str, _>("data"))
+        .map_err(|e| format!("Failed to deserialize stored item: {}", e))
+    })
+    .collect()
+}
+
+/// Mind maps stored in SQLite
+pub struct SqliteStorage {
+  pool: SqlitePool,
+}
+
+impl SqliteStorage {
+  /// Open the database (creating it and its tables if needed)
+  pub fn open<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<Self, String> {
+    if let Some(state) = app.try_state::<SqlitePoolState>() {
+      return Ok(Self { pool: state.0.clone() });
+    }
+
+    // Same directory the sql plugin resolves relative database paths against
+    let config_dir = app.path().app_config_dir()
+      .map_err(|e| format!("Failed to get app config directory: {}", e))?;
+
+    std::fs::create_dir_all(&config_dir)
+      .map_err(|e| format!("Failed to create app config directory: {}", e))?;
+
+    let options = SqliteConnectOptions::new()
+      .filename(config_dir.join(DATABASE_FILE_NAME))
+      .create_if_missing(true)
+      .foreign_keys(true);
+
+    let pool = block_on(async move {
+      let pool = SqlitePoolOptions::new()
+        .max_connections(4)
+        .connect_with(options)
+        .await
+        .map_err(db_error)?;
+
+      sqlx::raw_sql(SCHEMA).execute(&pool).await.map_err(db_error)?;
+      sqlx::raw_sql(UI_STATE_SCHEMA).execute(&pool).await.map_err(db_error)?;
+
+      Ok(pool)
+    })?;
+
+    println!("🗄️  Opened mind map database");
+
+    // Another thread may have opened it first, use whichever pool was stored
+    app.manage(SqlitePoolState(pool));
+
+    Ok(Self { pool: app.state::<SqlitePoolState>().0.clone() })
+  }
+}
+
+impl MindMapStorage for SqliteStorage {
+  fn load(&self, file_name: &str) -> Result<MindMap, String> {
+    let pool = self.pool.clone();
+    let file_name = file_name.to_string();
+
+    block_on(async move {
+      let row = sqlx::query(
+        "SELECT id, name, description, created_at, updated_at FROM mind_maps
+         WHERE file_name = ? AND deleted_at IS NULL"
+      )
+        .bind(&file_name)
+        .fetch_optional(&pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| format!("Mind map not found: {}", file_name))?;
+
+      let id: i64 = row.get("id");
+
+      let nodes = sqlx::query("SELECT data FROM mind_map_nodes WHERE mind_map_id = ? ORDER BY position")
+        .bind(id)
+        .fetch_all(&pool)
+        .await
+        .map_err(db_error)?;
+
+      let edges = sqlx::query("SELECT data FROM mind_map_edges WHERE mind_map_id = ? ORDER BY position")
+        .bind(id)
+        .fetch_all(&pool)
+        .await
+        .map_err(db_error)?;
+
+      let ui_state = sqlx::query("SELECT data FROM mind_map_ui_state WHERE mind_map_id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(db_error)?;
+
+      let ui_state = match ui_state {
+        Some(row) => serde_json::from_str(row.get::<&str, _>("data"))
+          .map_err(|e| format!("Failed to deserialize stored UI state: {}", e))?,
+        None => MindMapUiState::default(),
+      };
+
+      // Rows are always written from an upgraded mind map
+      Ok(MindMap {
+        version: CURRENT_FORMAT_VERSION,
+        id,
+        name: row.get("name"),
+        description: row.get("description"),
+        file_name,
+        nodes: parse_rows(nodes)?,
+        edges: parse_rows(edges)?,
+        ui_state,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+      })
+    })
+  }
+
+  fn save(&self, file_name: &str, mind_map: &MindMap) -> Result<(), String> {
+    let pool = self.pool.clone();
+    let file_name = file_name.to_string();
+    let mind_map = mind_map.clone();
+
+    block_on(async move {
+      let mut tx = pool.begin().await.map_err(db_error)?;
+
+      let id: i64 = sqlx::query(
+        "INSERT INTO mind_maps (file_name, name, description, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(file_name) DO UPDATE SET
+           name = excluded.name,
+           description = excluded.description,
+           updated_at = excluded.updated_at
+         RETURNING id"
+      )
+        .bind(&file_name)
+        .bind(&mind_map.name)
+        .bind(&mind_map.description)
+        .bind(&mind_map.created_at)
+        .bind(&mind_map.updated_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .get("id");
+
+      // Nodes and edges are replaced as a whole, like the JSON file
+      sqlx::query("DELETE FROM mind_map_nodes WHERE mind_map_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+      sqlx::query("DELETE FROM mind_map_edges WHERE mind_map_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+      for (position, node) in mind_map.nodes.iter().enumerate() {
+        sqlx::query(
+          "INSERT INTO mind_map_nodes (mind_map_id, position, node_id, node_type, data) VALUES (?, ?, ?, ?, ?)"
+        )
+          .bind(id)
+          .bind(position as i64)
+          .bind(node.id().unwrap_or_default())
+          .bind(node.node_type())
+          .bind(to_json(node)?)
+          .execute(&mut *tx)
+          .await
+          .map_err(db_error)?;
+      }
+
+      for (position, edge) in mind_map.edges.iter().enumerate() {
+        sqlx::query(
+          "INSERT INTO mind_map_edges (mind_map_id, position, edge_id, source, target, data) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+          .bind(id)
+          .bind(position as i64)
+          .bind(edge.id().unwrap_or_default())
+          .bind(edge.source())
+          .bind(edge.target())
+          .bind(to_json(edge)?)
+          .execute(&mut *tx)
+          .await
+          .map_err(db_error)?;
+      }
+
+      sqlx::query(
+        "INSERT INTO mind_map_ui_state (mind_map_id, data) VALUES (?, ?)
+         ON CONFLICT(mind_map_id) DO UPDATE SET data = excluded.data"
+      )
+        .bind(id)
+        .bind(to_json(&mind_map.ui_state)?)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+      tx.commit().await.map_err(db_error)?;
+
+      println!("💾 Mind map written to database: {}", file_name);
+
+      Ok(())
+    })
+  }
+
+  fn exists(&self, file_name: &str) -> Result<bool, String> {
+    let pool = self.pool.clone();
+    let file_name = file_name.to_string();
+
+    block_on(async move {
+      sqlx::query("SELECT 1 FROM mind_maps WHERE file_name = ? AND deleted_at IS NULL")
+        .bind(file_name)
+        .fetch_optional(&pool)
+        .await
+        .map(|row| row.is_some())
+        .map_err(db_error)
+    })
+  }
+
+  fn remove(&self, file_name: &str) -> Result<(), String> {
+    let pool = self.pool.clone();
+    let file_name = file_name.to_string();
+
+    block_on(async move {
+      sqlx::query("DELETE FROM mind_maps WHERE file_name = ?")
+        .bind(file_name)
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(db_error)
+    })
+  }
+
+  fn trash(&self, file_name: &str) -> Result<(), String> {
+    let pool = self.pool.clone();
+    let file_name = file_name.to_string();
+
+    block_on(async move {
+      let now = chrono::Utc::now();
+      let trashed_name = format!("{}{}_{}", TRASH_PREFIX, now.format("%Y%m%dT%H%M%S%3fZ"), file_name);
+
+      let result = sqlx::query(
+        "UPDATE mind_maps SET deleted_at = ?, file_name = ? WHERE file_name = ? AND deleted_at IS NULL"
+      )
+        .bind(now.to_rfc3339())
+        .bind(&trashed_name)
+        .bind(&file_name)
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+      if result.rows_affected() == 0 {
+        return Err(format!("Mind map not found: {}", file_name));
+      }
+
+      println!("🗑️  Mind map moved to trash: {}", trashed_name);
+
+      Ok(())
+    })
+  }
+
+  fn list(&self) -> Result<Vec<MindMapSummary>, String> {
+    let pool = self.pool.clone();
+
+    block_on(async move {
+      let rows = sqlx::query(
+        "SELECT m.file_name, m.name, m.description, m.created_at, m.updated_at,
+           (SELECT COUNT(*) FROM mind_map_nodes n WHERE n.mind_map_id = m.id) AS node_count,
+           (SELECT COUNT(*) FROM mind_map_edges e WHERE e.mind_map_id = m.id) AS edge_count,
+           (SELECT COALESCE(SUM(LENGTH(n.data)), 0) FROM mind_map_nodes n WHERE n.mind_map_id = m.id)
+             + (SELECT COALESCE(SUM(LENGTH(e.data)), 0) FROM mind_map_edges e WHERE e.mind_map_id = m.id) AS data_size
+         FROM mind_maps m
+         WHERE m.deleted_at IS NULL
+         ORDER BY m.updated_at DESC"
+      )
+        .fetch_all(&pool)
+        .await
+        .map_err(db_error)?;
+
+      Ok(rows.iter()
+        .map(|row| {
+          let updated_at: String = row.get("updated_at");
+
+          MindMapSummary {
+            file_name: row.get("file_name"),
+            name: row.get("name"),
+            description: row.get("description"),
+            created_at: row.get("created_at"),
+            modified_at: Some(updated_at.clone()),
+            updated_at,
+            node_count: row.get::<i64, _>("node_count") as usize,
+            edge_count: row.get::<i64, _>("edge_count") as usize,
+            file_size: row.get::<i64, _>("data_size") as u64,
+            error: None,
+          }
+        })
+        .collect())
+    })
+  }
+}
@@ -3,8 +3,15 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tauri::Manager;
 
+use super::format_migrations::{CURRENT_FORMAT_VERSION, UNVERSIONED_FORMAT_VERSION};
+use super::nodes::{self, MindMapEdge, MindMapNode};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MindMap {
+  /// File format version, see `format_migrations`
+  #[serde(default = "unversioned_format")]
+  pub(crate) version: u32,
+
   pub(crate) id: i64,
   pub(crate) name: String,
   pub(crate) description: String,
@@ -12,22 +19,63 @@ pub struct MindMap {
   #[serde(rename = "fileName")]
   pub(crate) file_name: String,
 
-  pub(crate) nodes: serde_json::Value,
-  pub(crate) edges: serde_json::Value,
+  #[serde(default, deserialize_with = "nodes::deserialize_list")]
+  pub(crate) nodes: Vec<MindMapNode>,
+  #[serde(default, deserialize_with = "nodes::deserialize_list")]
+  pub(crate) edges: Vec<MindMapEdge>,
+
+  /// Viewport, layout and collapsed branches, stored alongside the map's own fields
+  #[serde(flatten)]
+  pub(crate) ui_state: MindMapUiState,
 
   pub(crate) created_at: String,
   pub(crate) updated_at: String,
 }
 
+/// Where the canvas is panned and zoomed to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Viewport {
+  pub x: f64,
+  pub y: f64,
+  pub zoom: f64,
+}
+
+impl Default for Viewport {
+  fn default() -> Self {
+    Self { x: 0.0, y: 0.0, zoom: 1.0 }
+  }
+}
+
+/// Per-map UI state
+/// Saved with the map, but changing it doesn't mark the map as unsaved
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MindMapUiState {
+  #[serde(default)]
+  pub viewport: Viewport,
+  /// Direction new branches are laid out in (e.g. "horizontal", "vertical")
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub layout_direction: Option<String>,
+  /// Nodes whose branches are collapsed
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub collapsed_node_ids: Vec<String>,
+}
+
+fn unversioned_format() -> u32 {
+  UNVERSIONED_FORMAT_VERSION
+}
+
 impl Default for MindMap {
   fn default() -> Self {
     Self {
+      version: CURRENT_FORMAT_VERSION,
       id: 0,
       name: "Untitled".to_string(),
       description: "No description".to_string(),
       file_name: "".to_string(),
-      nodes: serde_json::json!([]),
-      edges: serde_json::json!([]),
+      nodes: vec![],
+      edges: vec![],
+      ui_state: MindMapUiState::default(),
       created_at: Utc::now().to_rfc3339(),
       updated_at: Utc::now().to_rfc3339(),
     }
@@ -67,17 +115,65 @@ pub struct SavingStatePayload {
   pub is_saving: bool,
 }
 
+/// Error returned by `flush_mind_map`
+///
+/// `Conflict` means the file changed on disk since it was loaded or last saved;
+/// the frontend resolves it with `resolve_flush_conflict`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FlushError {
+  #[serde(rename_all = "camelCase")]
+  Conflict { file_name: String, message: String },
+  Failed { message: String },
+}
+
+impl From<String> for FlushError {
+  fn from(message: String) -> Self {
+    FlushError::Failed { message }
+  }
+}
+
+impl std::fmt::Display for FlushError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      FlushError::Conflict { message, .. } | FlushError::Failed { message } => write!(f, "{}", message),
+    }
+  }
+}
+
+/// How to resolve a flush conflict
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictResolution {
+  /// Write the local version over the changed file
+  Overwrite,
+  /// Discard local edits and load the file from disk
+  Reload,
+  /// Write the local version to a copy and open it, leaving the file as is
+  SaveAsCopy,
+}
+
+/// Why a mind map could not be loaded
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MindMapLoadError {
+  pub file_name: String,
+  pub message: String,
+}
+
 // Helper functions for creating mind maps
 
 /// Create an empty default mind map
 pub fn create_empty_mind_map() -> MindMap {
   MindMap {
+    version: CURRENT_FORMAT_VERSION,
     id: 0,
     name: "Untitled".to_string(),
     description: "".to_string(),
     file_name: "".to_string(),
-    nodes: serde_json::json!([]),
-    edges: serde_json::json!([]),
+    nodes: vec![],
+    edges: vec![],
+    ui_state: MindMapUiState::default(),
     created_at: Utc::now().to_rfc3339(),
     updated_at: Utc::now().to_rfc3339(),
   }
@@ -86,41 +182,37 @@ pub fn create_empty_mind_map() -> MindMap {
 /// Create tutorial mind map for first-time users
 pub fn create_tutorial_mind_map() -> MindMap {
   MindMap {
+    version: CURRENT_FORMAT_VERSION,
     id: 0,
     name: "Tutorial".to_string(),
     description: "Learn how to use AI Mind Map".to_string(),
     file_name: "tutorial.json".to_string(),
-    nodes: serde_json::json!([
-      {
+    nodes: vec![
+      serde_json::json!({
         "id": "welcome",
-        "type": "llmPrompt",
+        "type": nodes::CHAT_NODE_TYPE,
         "position": { "x": 0, "y": 0 },
         "data": {
-          "userMessage": {
-            "role": "user",
-            "content": "Welcome to AI Mind Map! This is a tutorial node. Try adding more nodes and connecting them to create your mind map."
-          }
+          "content": "Welcome to AI Mind Map! This is a tutorial node. Try adding more nodes and connecting them to create your mind map."
         }
-      },
-      {
+      }).into(),
+      serde_json::json!({
         "id": "tip1",
-        "type": "llmPrompt",
+        "type": nodes::CHAT_NODE_TYPE,
         "position": { "x": 300, "y": 0 },
         "data": {
-          "userMessage": {
-            "role": "user",
-            "content": "Tip: You can drag nodes around to organize your thoughts. Connect nodes by dragging from one node's handle to another."
-          }
+          "content": "Tip: You can drag nodes around to organize your thoughts. Connect nodes by dragging from one node's handle to another."
         }
-      }
-    ]),
-    edges: serde_json::json!([
-      {
+      }).into()
+    ],
+    edges: vec![
+      serde_json::json!({
         "id": "e-welcome-tip1",
         "source": "welcome",
         "target": "tip1"
-      }
-    ]),
+      }).into()
+    ],
+    ui_state: MindMapUiState::default(),
     created_at: Utc::now().to_rfc3339(),
     updated_at: Utc::now().to_rfc3339(),
   }
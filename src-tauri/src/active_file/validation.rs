@@ -0,0 +1,386 @@
+// Structural validation - problems in the node graph that break thread collection
+//
+// Conversation threads are collected by walking from a node up through its
+// parents, which only works when ids are unique, every edge connects existing
+// nodes, the graph has no cycles and only merge nodes (summary nodes) have more
+// than one parent. `validate` reports violations as diagnostics and `repair`
+// fixes the ones that can be fixed without guessing what the user meant.
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::types::MindMap;
+
+/// Sent when a loaded mind map had structural problems
+pub const DIAGNOSTICS_EVENT: &str = "aiMindMap://mindMap/diagnostics";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticKind {
+  /// A node without an id
+  MissingNodeId,
+  /// Several nodes share an id
+  DuplicateNodeId,
+  /// An edge whose source or target doesn't exist
+  DanglingEdge,
+  /// An edge connecting a node to itself
+  SelfLoop,
+  /// Several edges between the same two nodes
+  DuplicateEdge,
+  /// Nodes that are their own ancestors
+  Cycle,
+  /// A regular node with more than one parent
+  MultipleParents,
+}
+
+/// A structural problem in a mind map
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MindMapDiagnostic {
+  pub kind: DiagnosticKind,
+  pub message: String,
+  pub node_ids: Vec<String>,
+  pub edge_ids: Vec<String>,
+  /// Whether `repair` fixes this problem
+  pub repairable: bool,
+}
+
+impl MindMapDiagnostic {
+  fn new(kind: DiagnosticKind, message: String, node_ids: Vec<String>, edge_ids: Vec<String>) -> Self {
+    let repairable = !matches!(kind, DiagnosticKind::Cycle | DiagnosticKind::MultipleParents);
+    Self { kind, message, node_ids, edge_ids, repairable }
+  }
+}
+
+/// Result of validating or repairing a mind map
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+  pub file_name: String,
+  /// Problems still present in the mind map
+  pub diagnostics: Vec<MindMapDiagnostic>,
+  /// Problems that were fixed (only set by a repair)
+  pub repaired: Vec<MindMapDiagnostic>,
+}
+
+impl ValidationReport {
+  pub fn is_clean(&self) -> bool {
+    self.diagnostics.is_empty() && self.repaired.is_empty()
+  }
+}
+
+/// Check a mind map for structural problems
+pub(crate) fn validate(mind_map: &MindMap) -> Vec<MindMapDiagnostic> {
+  let mut diagnostics = vec![];
+
+  // Node ids
+  let mut node_ids: HashSet<&str> = HashSet::new();
+  for (index, node) in mind_map.nodes.iter().enumerate() {
+    match node.id() {
+      None => diagnostics.push(MindMapDiagnostic::new(
+        DiagnosticKind::MissingNodeId,
+        format!("Node #{} ({}) has no id", index, node.node_type()),
+        vec![],
+        vec![],
+      )),
+      Some(id) if !node_ids.insert(id) => diagnostics.push(MindMapDiagnostic::new(
+        DiagnosticKind::DuplicateNodeId,
+        format!("Several nodes have the id {}", id),
+        vec![id.to_string()],
+        vec![],
+      )),
+      Some(_) => {}
+    }
+  }
+
+  // Edges, keeping the valid ones for the graph checks below
+  let mut pairs: HashSet<(&str, &str)> = HashSet::new();
+  let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+  let mut parent_edges: HashMap<&str, Vec<(&str, &str)>> = HashMap::new();
+
+  for edge in &mind_map.edges {
+    let edge_id = edge.id().unwrap_or_default();
+
+    let (Some(source), Some(target)) = (edge.source(), edge.target()) else {
+      diagnostics.push(MindMapDiagnostic::new(
+        DiagnosticKind::DanglingEdge,
+        format!("Edge {} is missing its source or target", edge_id),
+        vec![],
+        vec![edge_id.to_string()],
+      ));
+      continue;
+    };
+
+    let missing: Vec<&str> = [source, target].into_iter().filter(|id| !node_ids.contains(id)).collect();
+    if !missing.is_empty() {
+      diagnostics.push(MindMapDiagnostic::new(
+        DiagnosticKind::DanglingEdge,
+        format!("Edge {} points to missing node {}", edge_id, missing.join(", ")),
+        missing.iter().map(|id| id.to_string()).collect(),
+        vec![edge_id.to_string()],
+      ));
+      continue;
+    }
+
+    if source == target {
+      diagnostics.push(MindMapDiagnostic::new(
+        DiagnosticKind::SelfLoop,
+        format!("Edge {} connects node {} to itself", edge_id, source),
+        vec![source.to_string()],
+        vec![edge_id.to_string()],
+      ));
+      continue;
+    }
+
+    if !pairs.insert((source, target)) {
+      diagnostics.push(MindMapDiagnostic::new(
+        DiagnosticKind::DuplicateEdge,
+        format!("Several edges connect {} to {}", source, target),
+        vec![source.to_string(), target.to_string()],
+        vec![edge_id.to_string()],
+      ));
+      continue;
+    }
+
+    children.entry(source).or_default().push(target);
+    parent_edges.entry(target).or_default().push((source, edge_id));
+  }
+
+  // Parents (checked against the first node with each id)
+  let mut checked: HashSet<&str> = HashSet::new();
+  for node in &mind_map.nodes {
+    let Some(id) = node.id() else { continue };
+
+    if !checked.insert(id) || node.allows_multiple_parents() {
+      continue;
+    }
+
+    if let Some(parents) = parent_edges.get(id).filter(|parents| parents.len() > 1) {
+      let mut ids = vec![id.to_string()];
+      ids.extend(parents.iter().map(|(parent, _)| parent.to_string()));
+
+      diagnostics.push(MindMapDiagnostic::new(
+        DiagnosticKind::MultipleParents,
+        format!("Node {} has {} parents, only summary nodes can have more than one", id, parents.len()),
+        ids,
+        parents.iter().map(|(_, edge_id)| edge_id.to_string()).collect(),
+      ));
+    }
+  }
+
+  // Cycles
+  for cycle in find_cycles(&mind_map.nodes.iter().filter_map(|n| n.id()).collect::<Vec<_>>(), &children) {
+    diagnostics.push(MindMapDiagnostic::new(
+      DiagnosticKind::Cycle,
+      format!("Nodes form a cycle: {}", cycle.join(" -> ")),
+      cycle,
+      vec![],
+    ));
+  }
+
+  diagnostics
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+  InProgress,
+  Done,
+}
+
+/// Depth-first search for cycles, each reported as the nodes along it
+/// Walks with an explicit stack, long chains of nodes would overflow a recursive one
+fn find_cycles<'a>(node_ids: &[&'a str], children: &HashMap<&'a str, Vec<&'a str>>) -> Vec<Vec<String>> {
+  let mut visits: HashMap<&str, Visit> = HashMap::new();
+  let mut cycles = vec![];
+
+  for &root in node_ids {
+    if visits.contains_key(root) {
+      continue;
+    }
+
+    // The path from the root, each node with the index of its next child to visit
+    let mut path: Vec<(&str, usize)> = vec![(root, 0)];
+    visits.insert(root, Visit::InProgress);
+
+    while let Some((node, next)) = path.last_mut() {
+      let node = *node;
+
+      let Some(&child) = children.get(node).and_then(|c| c.get(*next)) else {
+        visits.insert(node, Visit::Done);
+        path.pop();
+        continue;
+      };
+
+      *next += 1;
+
+      match visits.get(child) {
+        None => {
+          visits.insert(child, Visit::InProgress);
+          path.push((child, 0));
+        }
+        Some(Visit::InProgress) => {
+          let start = path.iter().position(|(n, _)| *n == child).unwrap_or(0);
+          cycles.push(path[start..].iter().map(|(n, _)| n.to_string()).collect());
+        }
+        Some(Visit::Done) => {}
+      }
+    }
+  }
+
+  cycles
+}
+
+/// Fix the problems that can be fixed safely and report what was done
+///
+/// Nodes without an id or with a duplicate id get a new one (edges keep
+/// pointing at the first node with the id), and dangling, self-looping and
+/// duplicate edges are removed. Cycles and extra parents are left for the
+/// user, since there is no way to tell which edge is the wrong one.
+pub(crate) fn repair(mind_map: &mut MindMap) -> ValidationReport {
+  let repaired: Vec<MindMapDiagnostic> = validate(mind_map)
+    .into_iter()
+    .filter(|diagnostic| diagnostic.repairable)
+    .collect();
+
+  if !repaired.is_empty() {
+    let mut node_ids: HashSet<String> = HashSet::new();
+
+    for node in mind_map.nodes.iter_mut() {
+      let unique = node.id().is_some_and(|id| node_ids.insert(id.to_string()));
+
+      if !unique {
+        let id = uuid::Uuid::new_v4().to_string();
+        node.set_id(id.clone());
+        node_ids.insert(id);
+      }
+    }
+
+    let mut pairs: HashSet<(String, String)> = HashSet::new();
+
+    mind_map.edges.retain(|edge| match (edge.source(), edge.target()) {
+      (Some(source), Some(target)) if source != target && node_ids.contains(source) && node_ids.contains(target) => {
+        pairs.insert((source.to_string(), target.to_string()))
+      }
+      _ => false,
+    });
+  }
+
+  ValidationReport {
+    file_name: mind_map.file_name.clone(),
+    diagnostics: validate(mind_map),
+    repaired,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::active_file::nodes::{CHAT_NODE_TYPE, SUMMARY_NODE_TYPE};
+  use crate::active_file::types::create_empty_mind_map;
+  use serde_json::{json, Value};
+
+  fn node(id: &str, node_type: &str) -> Value {
+    json!({ "id": id, "type": node_type, "position": { "x": 0, "y": 0 }, "data": {} })
+  }
+
+  fn edge(id: &str, source: &str, target: &str) -> Value {
+    json!({ "id": id, "source": source, "target": target })
+  }
+
+  fn mind_map(nodes: Vec<Value>, edges: Vec<Value>) -> MindMap {
+    let mut mind_map = create_empty_mind_map();
+    mind_map.nodes = nodes.into_iter().map(Into::into).collect();
+    mind_map.edges = edges.into_iter().map(Into::into).collect();
+    mind_map
+  }
+
+  fn kinds(diagnostics: &[MindMapDiagnostic]) -> Vec<DiagnosticKind> {
+    diagnostics.iter().map(|d| d.kind).collect()
+  }
+
+  #[test]
+  fn valid_tree_has_no_diagnostics() {
+    let map = mind_map(
+      vec![node("a", CHAT_NODE_TYPE), node("b", CHAT_NODE_TYPE), node("c", CHAT_NODE_TYPE)],
+      vec![edge("e1", "a", "b"), edge("e2", "a", "c")],
+    );
+
+    assert!(validate(&map).is_empty());
+  }
+
+  #[test]
+  fn reports_broken_edges_and_duplicate_ids() {
+    let map = mind_map(
+      vec![node("a", CHAT_NODE_TYPE), node("a", CHAT_NODE_TYPE), node("b", CHAT_NODE_TYPE)],
+      vec![edge("e1", "a", "missing"), edge("e2", "b", "b"), edge("e3", "a", "b"), edge("e4", "a", "b")],
+    );
+
+    assert_eq!(kinds(&validate(&map)), vec![
+      DiagnosticKind::DuplicateNodeId,
+      DiagnosticKind::DanglingEdge,
+      DiagnosticKind::SelfLoop,
+      DiagnosticKind::DuplicateEdge,
+    ]);
+  }
+
+  #[test]
+  fn only_summary_nodes_may_have_several_parents() {
+    let map = mind_map(
+      vec![node("a", CHAT_NODE_TYPE), node("b", CHAT_NODE_TYPE), node("c", CHAT_NODE_TYPE), node("s", SUMMARY_NODE_TYPE)],
+      vec![edge("e1", "a", "c"), edge("e2", "b", "c"), edge("e3", "a", "s"), edge("e4", "b", "s")],
+    );
+
+    let diagnostics = validate(&map);
+    assert_eq!(kinds(&diagnostics), vec![DiagnosticKind::MultipleParents]);
+    assert_eq!(diagnostics[0].node_ids, vec!["c", "a", "b"]);
+  }
+
+  #[test]
+  fn reports_cycles_along_their_path() {
+    let map = mind_map(
+      vec![node("a", CHAT_NODE_TYPE), node("b", CHAT_NODE_TYPE), node("c", CHAT_NODE_TYPE)],
+      vec![edge("e1", "a", "b"), edge("e2", "b", "c"), edge("e3", "c", "a")],
+    );
+
+    let diagnostics = validate(&map);
+    assert_eq!(kinds(&diagnostics), vec![DiagnosticKind::Cycle]);
+    assert_eq!(diagnostics[0].node_ids, vec!["a", "b", "c"]);
+  }
+
+  #[test]
+  fn long_chains_do_not_overflow_the_stack() {
+    let count = 100_000;
+    let nodes = (0..count).map(|i| node(&i.to_string(), CHAT_NODE_TYPE)).collect();
+    let edges = (1..count).map(|i| edge(&format!("e{}", i), &(i - 1).to_string(), &i.to_string())).collect();
+
+    assert!(validate(&mind_map(nodes, edges)).is_empty());
+  }
+
+  #[test]
+  fn repair_fixes_ids_and_edges_but_leaves_cycles() {
+    let mut map = mind_map(
+      vec![node("a", CHAT_NODE_TYPE), node("a", CHAT_NODE_TYPE), node("b", CHAT_NODE_TYPE)],
+      vec![edge("e1", "a", "missing"), edge("e2", "b", "b"), edge("e3", "a", "b"), edge("e4", "b", "a")],
+    );
+
+    let report = repair(&mut map);
+
+    assert_eq!(kinds(&report.repaired), vec![
+      DiagnosticKind::DuplicateNodeId,
+      DiagnosticKind::DanglingEdge,
+      DiagnosticKind::SelfLoop,
+    ]);
+    assert_eq!(kinds(&report.diagnostics), vec![DiagnosticKind::Cycle]);
+    assert_ne!(map.nodes[1].id(), Some("a"));
+    assert_eq!(map.edges.iter().filter_map(|e| e.id()).collect::<Vec<_>>(), vec!["e3", "e4"]);
+  }
+
+  #[test]
+  fn repairing_a_valid_map_changes_nothing() {
+    let mut map = mind_map(vec![node("a", CHAT_NODE_TYPE), node("b", CHAT_NODE_TYPE)], vec![edge("e1", "a", "b")]);
+    let before = map.clone();
+
+    assert!(repair(&mut map).is_clean());
+    assert_eq!(map.nodes, before.nodes);
+    assert_eq!(map.edges, before.edges);
+  }
+}
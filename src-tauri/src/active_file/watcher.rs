@@ -0,0 +1,235 @@
+// File watcher - picks up external edits to the active mind map
+//
+// The directory of the active map is watched, since editors and sync tools
+// (Dropbox, Syncthing) usually replace files instead of writing in place. When
+// the file's content differs from what the app last read or wrote, the map is
+// reloaded if there are no unsaved local edits; otherwise a conflict event
+// carries both versions to the frontend.
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::cache::update_cache;
+use super::commands::{emit_state_update, mark_loaded, update_window_title};
+use super::manager::MindMapManager;
+use super::persistence::{self, load_and_repair_mind_map, StorageBackend};
+use super::types::MindMap;
+use super::validation;
+use crate::files;
+use crate::state::AppState;
+
+pub const CONFLICT_EVENT: &str = "aiMindMap://mindMap/conflict";
+
+/// How often the watched path is synced with the active map and events are handled
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Quiet time after the last event before the file is read (writes come in bursts)
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// Sent when the active mind map changed on disk while it has unsaved edits
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalChangeConflict {
+  pub file_name: String,
+  pub local: MindMap,
+  pub disk: MindMap,
+}
+
+/// The file currently being watched
+struct WatchedFile {
+  file_name: String,
+  path: PathBuf,
+  dir: Option<PathBuf>,
+}
+
+/// Remember the current disk content of a file if nothing was recorded for it yet
+fn record_baseline(manager: &MindMapManager, file_name: &str, path: &Path) {
+  if manager.get_disk_fingerprint(file_name).is_some() {
+    return;
+  }
+
+  if let Ok(contents) = std::fs::read(path) {
+    manager.record_disk_state(file_name, &contents);
+  }
+}
+
+/// Point the watcher at the directory of the active mind map
+fn sync_watched_file<R: tauri::Runtime, W: Watcher>(
+  app: &AppHandle<R>,
+  watcher: &mut W,
+  watched: &mut Option<WatchedFile>
+) {
+  let manager = app.state::<MindMapManager>();
+
+  // Maps stored in the database have no file of their own to watch
+  let current = match app.state::<AppState>().get_storage_backend() {
+    StorageBackend::Json => manager.get_current_path(),
+    StorageBackend::Sqlite => String::new(),
+  };
+
+  if watched.as_ref().map(|w| w.file_name.as_str()).unwrap_or("") == current {
+    return;
+  }
+
+  if let Some(dir) = watched.take().and_then(|w| w.dir) {
+    let _ = watcher.unwatch(&dir);
+  }
+
+  // Never-saved maps have no file to watch
+  if current.is_empty() {
+    return;
+  }
+
+  let path = match files::resolve_mind_map_path(app, &current) {
+    Ok(path) => path,
+    Err(e) => {
+      eprintln!("⚠️  Not watching {}: {}", current, e);
+      *watched = Some(WatchedFile { file_name: current, path: PathBuf::new(), dir: None });
+      return;
+    }
+  };
+
+  let dir = path.parent().and_then(|dir| {
+    match watcher.watch(dir, RecursiveMode::NonRecursive) {
+      Ok(_) => Some(dir.to_path_buf()),
+      Err(e) => {
+        eprintln!("⚠️  Failed to watch {:?}: {}", dir, e);
+        None
+      }
+    }
+  });
+
+  if dir.is_some() {
+    println!("👀 Watching {} for external changes", current);
+  }
+
+  record_baseline(&manager, &current, &path);
+
+  *watched = Some(WatchedFile { file_name: current, path, dir });
+}
+
+/// Reload the active map or report a conflict if its file changed on disk
+/// Returns the fingerprint of a reported conflict so it is only sent once
+fn check_for_external_change<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  watched: &WatchedFile,
+  reported_conflict: Option<u64>
+) -> Result<Option<u64>, String> {
+  let manager = app.state::<MindMapManager>();
+
+  // The active map may have changed since the event
+  if manager.get_current_path() != watched.file_name {
+    return Ok(None);
+  }
+
+  // Removed, or in the middle of being replaced; a later event follows
+  let Ok(contents) = std::fs::read_to_string(&watched.path) else {
+    return Ok(reported_conflict);
+  };
+
+  let fingerprint = files::content_fingerprint(contents.as_bytes());
+  if manager.get_disk_fingerprint(&watched.file_name) == Some(fingerprint) || reported_conflict == Some(fingerprint) {
+    return Ok(reported_conflict);
+  }
+
+  if !manager.is_saved() {
+    // Only parsed, the file stays as it is until the user decides what to keep
+    let mut disk = persistence::parse_mind_map(&contents)
+      .map_err(|e| format!("Ignoring unreadable external change to {}: {}", watched.file_name, e))?;
+    disk.file_name = watched.file_name.clone();
+    validation::repair(&mut disk);
+
+    println!("⚠️  {} changed on disk while it has unsaved edits", watched.file_name);
+
+    app.emit(CONFLICT_EVENT, ExternalChangeConflict {
+      file_name: watched.file_name.clone(),
+      local: manager.get_active_mind_map(),
+      disk,
+    }).map_err(|e| format!("Failed to emit conflict event: {}", e))?;
+
+    return Ok(Some(fingerprint));
+  }
+
+  // Goes through format upgrades and repairs like any other load, which also records the disk state
+  let (disk, repaired) = load_and_repair_mind_map(app, &watched.file_name)
+    .map_err(|e| format!("Ignoring unreadable external change to {}: {}", watched.file_name, e))?;
+
+  update_cache(&manager, watched.file_name.clone(), disk.clone());
+  manager.set_active_mind_map(disk.clone(), watched.file_name.clone());
+  mark_loaded(&manager, repaired);
+
+  println!("🔄 Reloaded {} after an external change", watched.file_name);
+
+  update_window_title(app, &disk)?;
+  emit_state_update(app, &disk)?;
+
+  Ok(None)
+}
+
+/// Start watching the active mind map's file for external changes
+pub fn start_file_watcher<R: tauri::Runtime + 'static>(app: AppHandle<R>) {
+  let (sender, receiver) = mpsc::channel::<PathBuf>();
+
+  let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+    if let Ok(event) = result {
+      if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+        for path in event.paths {
+          let _ = sender.send(path);
+        }
+      }
+    }
+  });
+
+  let mut watcher = match watcher {
+    Ok(watcher) => watcher,
+    Err(e) => {
+      eprintln!("⚠️  Failed to start file watcher: {}", e);
+      return;
+    }
+  };
+
+  tauri::async_runtime::spawn(async move {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    let mut watched: Option<WatchedFile> = None;
+    let mut last_event_at: Option<Instant> = None;
+    let mut reported_conflict: Option<u64> = None;
+
+    loop {
+      interval.tick().await;
+
+      let previous = watched.as_ref().map(|w| w.file_name.clone());
+      sync_watched_file(&app, &mut watcher, &mut watched);
+
+      if watched.as_ref().map(|w| w.file_name.clone()) != previous {
+        last_event_at = None;
+        reported_conflict = None;
+      }
+
+      let Some(current) = watched.as_ref() else {
+        while receiver.try_recv().is_ok() {}
+        continue;
+      };
+
+      // Only the active file matters, the rest of its directory is ignored
+      while let Ok(path) = receiver.try_recv() {
+        if path.file_name() == current.path.file_name() {
+          last_event_at = Some(Instant::now());
+        }
+      }
+
+      if last_event_at.is_none_or(|at| at.elapsed() < SETTLE_DELAY) {
+        continue;
+      }
+
+      last_event_at = None;
+
+      match check_for_external_change(&app, current, reported_conflict) {
+        Ok(conflict) => reported_conflict = conflict,
+        Err(e) => eprintln!("⚠️  {}", e),
+      }
+    }
+  });
+}
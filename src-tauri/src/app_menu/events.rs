@@ -89,6 +89,116 @@ pub (crate) fn on_save<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) {
   }
 }
 
+pub (crate) fn on_save_as<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) {
+  println!("💾 Save As menu item clicked");
+
+  let app_handle = app_handle.clone();
+
+  // Spawn async task to handle the save dialog
+  tauri::async_runtime::spawn(async move {
+    use crate::active_file::commands::save_mind_map_as;
+
+    let manager = app_handle.state::<MindMapManager>();
+
+    match save_mind_map_as(manager, app_handle.clone(), None).await {
+      Ok(file_name) => println!("✅ File saved as {}", file_name),
+      Err(e) => eprintln!("⚠️  Failed to save file: {}", e),
+    }
+  });
+}
+
+pub (crate) fn on_rename<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) {
+  println!("✏️  Rename menu item clicked");
+
+  let app_handle = app_handle.clone();
+
+  // Spawn async task so the dialog doesn't block the menu event loop
+  tauri::async_runtime::spawn(async move {
+    use crate::active_file::commands::{pick_save_path, rename_mind_map};
+
+    let manager = app_handle.state::<MindMapManager>();
+    let current = manager.get_current_path();
+
+    if current.is_empty() {
+      eprintln!("⚠️  Save the mind map before renaming it");
+      return;
+    }
+
+    let suggested = std::path::Path::new(&current)
+      .file_name()
+      .and_then(|n| n.to_str())
+      .unwrap_or_default()
+      .to_string();
+
+    // Pass the picked path on as absolute so it isn't treated as a display name
+    let result = pick_save_path(&app_handle, "Rename Mind Map", &suggested)
+      .and_then(|path| {
+        path.to_str()
+          .map(|s| s.to_string())
+          .ok_or_else(|| format!("Invalid file path: {:?}", path))
+      })
+      .and_then(|new_name| rename_mind_map(manager, app_handle.clone(), None, new_name));
+
+    match result {
+      Ok(file_name) => println!("✅ File renamed to {}", file_name),
+      Err(e) => eprintln!("⚠️  Failed to rename file: {}", e),
+    }
+  });
+}
+
+pub (crate) fn on_duplicate<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) {
+  println!("📑 Duplicate menu item clicked");
+
+  use crate::active_file::commands::{duplicate_mind_map, load_mind_map};
+
+  // Copy the active mind map, then open the copy
+  let result = duplicate_mind_map(app_handle.state::<MindMapManager>(), app_handle.clone(), None)
+    .and_then(|file_name| load_mind_map(app_handle.state::<MindMapManager>(), app_handle.clone(), file_name));
+
+  match result {
+    Ok(_) => println!("✅ File duplicated successfully"),
+    Err(e) => eprintln!("⚠️  Failed to duplicate file: {}", e),
+  }
+}
+
+pub (crate) fn on_delete<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) {
+  println!("🗑️  Delete menu item clicked");
+
+  let app_handle = app_handle.clone();
+
+  // Spawn async task so the confirmation doesn't block the menu event loop
+  tauri::async_runtime::spawn(async move {
+    use crate::active_file::commands::delete_mind_map;
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let manager = app_handle.state::<MindMapManager>();
+    let current = manager.get_current_path();
+
+    if current.is_empty() {
+      eprintln!("⚠️  The mind map has not been saved, nothing to delete");
+      return;
+    }
+
+    let confirmed = app_handle
+      .dialog()
+      .message(format!("Move \"{}\" to the trash?", current))
+      .title("Delete Mind Map")
+      .kind(MessageDialogKind::Warning)
+      .buttons(MessageDialogButtons::OkCancelCustom("Delete".to_string(), "Cancel".to_string()))
+      .blocking_show();
+
+    if !confirmed {
+      println!("❌ Delete cancelled");
+      return;
+    }
+
+    match delete_mind_map(manager, app_handle.clone(), current) {
+      Ok(_) => println!("✅ File deleted successfully"),
+      Err(e) => eprintln!("⚠️  Failed to delete file: {}", e),
+    }
+  });
+}
+
 pub (crate) fn on_settings<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) {
   println!("📂 Show settings menu item clicked");
 
@@ -110,4 +220,17 @@ pub (crate) fn on_zen_mode<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>)
   println!("🧘 Zen mode menu item clicked");
 
   let _ = app_handle.emit("zen_mode_trigger", true);
-}
\ No newline at end of file
+}
+
+pub (crate) fn on_switch_profile<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>, profile_id: &str) {
+  println!("🔀 Switch server profile clicked: {}", profile_id);
+
+  let app_handle = app_handle.clone();
+  let profile_id = profile_id.to_string();
+
+  tauri::async_runtime::spawn(async move {
+    if let Err(e) = crate::ollama::profiles::activate_profile(&app_handle, &profile_id).await {
+      eprintln!("⚠️  Failed to switch server profile: {}", e);
+    }
+  });
+}
@@ -1,8 +1,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use crate::app_menu::events::{on_debug_viewport, on_new, on_open, on_save, on_settings, on_zen_mode};
-use crate::ollama::{OllamaConfig, OllamaStatus};
-use tauri::menu::{Menu, MenuBuilder, MenuItem, PredefinedMenuItem, SubmenuBuilder, CheckMenuItemBuilder};
+use crate::app_menu::events::{on_debug_viewport, on_delete, on_duplicate, on_new, on_open, on_rename, on_save, on_save_as, on_settings, on_switch_profile, on_zen_mode};
+use crate::AppState;
+use tauri::menu::{CheckMenuItem, Menu, MenuBuilder, MenuItem, PredefinedMenuItem, SubmenuBuilder, CheckMenuItemBuilder};
+use tauri::Manager;
 use tauri::tray::TrayIconBuilder;
 
 mod events;
@@ -11,6 +12,8 @@ mod events;
 pub const TRAY_MENU_ID: &str = "main";
 pub const TRAY_OLLAMA_SERVER_ID: &str = "ollama-server";
 pub const TRAY_OLLAMA_STATUS_ID: &str = "ollama-status";
+/// Tray items that switch profiles use this prefix followed by the profile id
+pub const TRAY_PROFILE_PREFIX: &str = "ollama-profile:";
 
 pub fn configure<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::Result<()> {
   configure_tray(app)?;
@@ -33,18 +36,19 @@ fn configure_tray<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::Result<()> {
   Ok(())
 }
 
-/// Update tray menu by rebuilding it with current config and status
-pub fn update_tray_ollama_info<R: tauri::Runtime>(
-  app: &tauri::AppHandle<R>,
-  config: &OllamaConfig,
-  status: &OllamaStatus,
-) {
+/// Update tray menu by rebuilding it with the active profile and every profile's status
+pub fn update_tray_ollama_info<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
   use tauri::tray::TrayIconId;
 
+  let app_state = app.state::<AppState>();
+  let active = app_state.get_active_profile();
+  let status = app_state.get_provider_status();
+  let statuses = app_state.get_profile_statuses();
+
   // Get the tray by ID
   if let Some(tray) = app.tray_by_id(&TrayIconId::new(TRAY_MENU_ID)) {
     // Build the updated menu text
-    let server_text = format!("Server: {}:{}", config.domain, config.port);
+    let server_text = format!("Server: {} ({}:{})", active.name, active.config.domain, active.config.port);
     let status_text = if status.is_available {
       format!("Status: Available ({} models)", status.models.len())
     } else {
@@ -77,6 +81,35 @@ pub fn update_tray_ollama_info<R: tauri::Runtime>(
       }
     };
 
+    // One checkable item per profile, the active one is checked
+    let profiles = app_state.get_profiles();
+    if profiles.len() > 1 {
+      if let Err(e) = PredefinedMenuItem::separator(app).and_then(|sep| menu.append(&sep)) {
+        eprintln!("⚠️  Failed to add tray separator: {}", e);
+      }
+
+      for profile in profiles {
+        let indicator = match statuses.get(&profile.id) {
+          Some(s) if s.is_available => "●",
+          Some(_) => "○",
+          None => "…",
+        };
+
+        let item = CheckMenuItem::with_id(
+          app,
+          format!("{}{}", TRAY_PROFILE_PREFIX, profile.id),
+          format!("{} {}", indicator, profile.name),
+          true,
+          profile.id == active.id,
+          None::<&str>
+        );
+
+        if let Err(e) = item.and_then(|item| menu.append(&item)) {
+          eprintln!("⚠️  Failed to create profile menu item: {}", e);
+        }
+      }
+    }
+
     // Set the new menu on the tray
     if let Err(e) = tray.set_menu(Some(menu)) {
       eprintln!("⚠️  Failed to set tray menu: {}", e);
@@ -90,11 +123,20 @@ fn configure_menus<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::Result<()>
   let new_item = MenuItem::with_id(app, "new", "New", true, Some("CmdOrCtrl+N"))?;
   let open_item = MenuItem::with_id(app, "open", "Open", true, Some("CmdOrCtrl+O"))?;
   let save_item = MenuItem::with_id(app, "save", "Save", true, Some("CmdOrCtrl+S"))?;
+  let save_as_item = MenuItem::with_id(app, "saveAs", "Save As…", true, Some("CmdOrCtrl+Shift+S"))?;
+  let rename_item = MenuItem::with_id(app, "rename", "Rename…", true, None::<&str>)?;
+  let duplicate_item = MenuItem::with_id(app, "duplicate", "Duplicate", true, None::<&str>)?;
+  let delete_item = MenuItem::with_id(app, "delete", "Delete…", true, None::<&str>)?;
 
   let default_menu = SubmenuBuilder::new(app, "default")
     .item(&new_item)
     .item(&open_item)
     .item(&save_item)
+    .item(&save_as_item)
+    .separator()
+    .item(&rename_item)
+    .item(&duplicate_item)
+    .item(&delete_item)
     .separator()
     .text("settings", "Settings")
     .separator()
@@ -146,9 +188,24 @@ fn configure_menus<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::Result<()>
       "save" => {
         on_save(app_handle);
       }
+      "saveAs" => {
+        on_save_as(app_handle);
+      }
+      "rename" => {
+        on_rename(app_handle);
+      }
+      "duplicate" => {
+        on_duplicate(app_handle);
+      }
+      "delete" => {
+        on_delete(app_handle);
+      }
       "settings" => {
         on_settings(app_handle);
       }
+      id if id.starts_with(TRAY_PROFILE_PREFIX) => {
+        on_switch_profile(app_handle, &id[TRAY_PROFILE_PREFIX.len()..]);
+      }
       _ => {} // Do nothing when there is no match
     }
   });
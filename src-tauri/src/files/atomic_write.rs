@@ -0,0 +1,182 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Path of the backup kept next to a file (`name.json` -> `name.json.bak`)
+pub fn backup_path(file_path: &Path) -> PathBuf {
+  let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+  name.push(".bak");
+  file_path.with_file_name(name)
+}
+
+/// Counter that makes temp names unique among the writers of this process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Fresh temp path next to a file (`.name.json.<pid>.<n>.tmp`)
+/// Concurrent writers to the same target each get their own
+fn temp_path(file_path: &Path) -> PathBuf {
+  let mut name = std::ffi::OsString::from(".");
+  name.push(file_path.file_name().unwrap_or_default());
+  name.push(format!(".{}.{}.tmp", std::process::id(), TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+  file_path.with_file_name(name)
+}
+
+/// Write and fsync a temp file that will replace `file_path`, returning its path
+fn write_temp(file_path: &Path, contents: &[u8]) -> Result<PathBuf, String> {
+  let temp_path = temp_path(file_path);
+
+  let write = || -> std::io::Result<()> {
+    let mut file = File::create(&temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+  };
+
+  if let Err(e) = write() {
+    let _ = std::fs::remove_file(&temp_path);
+    return Err(format!("Failed to write temp file {:?}: {}", temp_path, e));
+  }
+
+  Ok(temp_path)
+}
+
+/// Rename a temp file over `file_path`, removing the temp file if that fails
+fn replace_with(temp_path: &Path, file_path: &Path) -> Result<(), String> {
+  std::fs::rename(temp_path, file_path).map_err(|e| {
+    let _ = std::fs::remove_file(temp_path);
+    format!("Failed to replace {:?}: {}", file_path, e)
+  })
+}
+
+/// Write a file so that a crash or a full disk never leaves it half written.
+///
+/// The contents go to a temp file in the same directory, which is fsynced and
+/// then renamed over the target. The previous version is kept as a `.bak`,
+/// which is replaced the same way.
+pub fn write_atomic(file_path: &Path, contents: impl AsRef<[u8]>) -> Result<(), String> {
+  let dir = file_path.parent()
+    .ok_or_else(|| format!("Invalid file path: {:?}", file_path))?;
+
+  let temp_path = write_temp(file_path, contents.as_ref())?;
+
+  // Keep the last good version around before replacing it
+  if file_path.exists() {
+    let backup_path = backup_path(file_path);
+
+    let backed_up = std::fs::read(file_path)
+      .map_err(|e| e.to_string())
+      .and_then(|previous| write_temp(&backup_path, &previous))
+      .and_then(|backup_temp| replace_with(&backup_temp, &backup_path));
+
+    if let Err(e) = backed_up {
+      let _ = std::fs::remove_file(&temp_path);
+      return Err(format!("Failed to back up {:?}: {}", file_path, e));
+    }
+  }
+
+  replace_with(&temp_path, file_path)?;
+
+  // Persist the renames themselves (directories cannot be opened for syncing on Windows)
+  #[cfg(unix)]
+  if let Ok(dir) = File::open(dir) {
+    let _ = dir.sync_all();
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Empty directory of its own for each test
+  fn test_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("atomic-write-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn backup_path_appends_bak() {
+    assert_eq!(backup_path(Path::new("/data/map.json")), PathBuf::from("/data/map.json.bak"));
+  }
+
+  #[test]
+  fn writes_a_new_file_without_backup_or_temp_file() {
+    let dir = test_dir();
+    let file_path = dir.join("map.json");
+
+    write_atomic(&file_path, "first").unwrap();
+
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "first");
+    assert!(!backup_path(&file_path).exists());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn keeps_the_previous_version_as_backup() {
+    let dir = test_dir();
+    let file_path = dir.join("map.json");
+
+    write_atomic(&file_path, "first").unwrap();
+    write_atomic(&file_path, "second").unwrap();
+
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "second");
+    assert_eq!(std::fs::read_to_string(backup_path(&file_path)).unwrap(), "first");
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn fails_without_touching_the_target_when_the_directory_is_missing() {
+    let dir = test_dir();
+    let file_path = dir.join("missing").join("map.json");
+
+    assert!(write_atomic(&file_path, "contents").is_err());
+    assert!(!file_path.exists());
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn concurrent_writers_never_share_a_temp_file() {
+    let dir = test_dir();
+    let file_path = dir.join("map.json");
+    let contents: Vec<String> = (0..8).map(|n| n.to_string().repeat(100_000)).collect();
+
+    std::thread::scope(|scope| {
+      for content in &contents {
+        let file_path = &file_path;
+        scope.spawn(move || {
+          for _ in 0..5 {
+            write_atomic(file_path, content).unwrap();
+          }
+        });
+      }
+    });
+
+    assert!(contents.contains(&std::fs::read_to_string(&file_path).unwrap()));
+    assert!(contents.contains(&std::fs::read_to_string(backup_path(&file_path)).unwrap()));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn failed_backup_leaves_the_target_and_no_temp_files() {
+    let dir = test_dir();
+    let file_path = dir.join("map.json");
+
+    write_atomic(&file_path, "first").unwrap();
+    // A directory in the backup's place makes replacing it fail
+    std::fs::create_dir(backup_path(&file_path)).unwrap();
+
+    assert!(write_atomic(&file_path, "second").is_err());
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "first");
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
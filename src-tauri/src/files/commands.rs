@@ -1,3 +1,4 @@
+use super::image_files::load_image_base64;
 use super::text_files::load_text_file;
 use mime_guess::from_path;
 use serde::Serialize;
@@ -17,4 +18,12 @@ pub fn load_txt_file(filename: String) -> Result<FileResponse, String> {
     .to_string();
 
   Ok(FileResponse { content, mime_type })
-}
\ No newline at end of file
+}
+
+/// Load an image file as base64, e.g. to attach it to a chat message
+#[tauri::command]
+pub fn load_image_file(filename: String) -> Result<FileResponse, String> {
+  let (content, mime_type) = load_image_base64(Path::new(&filename))?;
+
+  Ok(FileResponse { content, mime_type })
+}
@@ -0,0 +1,99 @@
+use std::path::{Component, Path};
+
+/// Stem used when a name has no usable characters
+const DEFAULT_STEM: &str = "untitled";
+
+/// Turn a display name into a safe file name (`"Q1 Planning!"` -> `q1_planning.json`)
+///
+/// Lowercases, replaces whitespace with underscores and drops everything that
+/// is not alphanumeric, `_` or `-`, so the result can't contain separators or `..`.
+pub fn sanitize_file_name(name: &str) -> String {
+  let name = name.trim();
+  let name = name.strip_suffix(".json").unwrap_or(name);
+
+  let stem: String = name
+    .to_lowercase()
+    .chars()
+    .map(|c| if c.is_whitespace() { '_' } else { c })
+    .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+    .collect();
+
+  let stem = stem.trim_matches('_');
+
+  if stem.is_empty() {
+    format!("{}.json", DEFAULT_STEM)
+  } else {
+    format!("{}.json", stem)
+  }
+}
+
+/// First file name that isn't taken: `name.json`, `name_2.json`, `name_3.json`, ...
+/// Fails as soon as checking whether a name is taken fails
+pub fn unique_file_name(file_name: &str, is_taken: impl Fn(&str) -> Result<bool, String>) -> Result<String, String> {
+  let stem = file_name.strip_suffix(".json").unwrap_or(file_name);
+
+  (1..)
+    .map(|n| match n {
+      1 => format!("{}.json", stem),
+      n => format!("{}_{}.json", stem, n),
+    })
+    .find_map(|candidate| match is_taken(&candidate) {
+      Ok(true) => None,
+      Ok(false) => Some(Ok(candidate)),
+      Err(e) => Some(Err(e)),
+    })
+    .expect("unbounded range always yields a free name")
+}
+
+/// Check that a relative path stays inside the directory it is joined to
+pub fn is_contained_relative_path(path: &Path) -> bool {
+  path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sanitizes_display_names() {
+    assert_eq!(sanitize_file_name("Q1 Planning!"), "q1_planning.json");
+    assert_eq!(sanitize_file_name("  notes.json "), "notes.json");
+    assert_eq!(sanitize_file_name("Größe-2"), "größe-2.json");
+  }
+
+  #[test]
+  fn sanitized_names_cannot_leave_the_directory() {
+    assert_eq!(sanitize_file_name("../../etc/passwd"), "etcpasswd.json");
+    assert_eq!(sanitize_file_name("C:\\Users\\me"), "cusersme.json");
+  }
+
+  #[test]
+  fn empty_names_fall_back_to_untitled() {
+    assert_eq!(sanitize_file_name(""), "untitled.json");
+    assert_eq!(sanitize_file_name(" ?! "), "untitled.json");
+  }
+
+  #[test]
+  fn unique_names_count_up_from_two() {
+    let taken = ["plan.json", "plan_2.json"];
+    let is_taken = |name: &str| Ok(taken.contains(&name));
+
+    assert_eq!(unique_file_name("plan.json", is_taken).unwrap(), "plan_3.json");
+    assert_eq!(unique_file_name("other.json", is_taken).unwrap(), "other.json");
+  }
+
+  #[test]
+  fn unique_names_fail_when_a_lookup_fails() {
+    let result = unique_file_name("plan.json", |_| Err("database is locked".to_string()));
+
+    assert_eq!(result, Err("database is locked".to_string()));
+  }
+
+  #[test]
+  fn contained_paths_have_only_normal_components() {
+    assert!(is_contained_relative_path(Path::new("sub/plan.json")));
+    assert!(is_contained_relative_path(Path::new("./plan.json")));
+    assert!(!is_contained_relative_path(Path::new("../plan.json")));
+    assert!(!is_contained_relative_path(Path::new("/etc/passwd")));
+  }
+}
@@ -0,0 +1,22 @@
+use base64::Engine;
+use mime_guess::from_path;
+use std::path::Path;
+
+/// Load an image file and return its contents as base64 along with its mime type.
+/// Fails for files that do not look like images based on their extension.
+pub fn load_image_base64(file_path: &Path) -> Result<(String, String), String> {
+  if !file_path.exists() {
+    return Err(format!("File not found: {:?}", file_path));
+  }
+
+  let mime_type = from_path(file_path)
+    .first()
+    .filter(|mime| mime.type_() == mime_guess::mime::IMAGE)
+    .ok_or_else(|| format!("Not an image file: {:?}", file_path))?
+    .to_string();
+
+  let bytes = std::fs::read(file_path)
+    .map_err(|e| format!("Failed to read file: {}", e))?;
+
+  Ok((base64::engine::general_purpose::STANDARD.encode(bytes), mime_type))
+}
@@ -1,9 +1,15 @@
 use tauri::Manager;
 
 pub mod text_files;
+pub mod image_files;
+pub mod atomic_write;
+pub mod file_names;
 pub mod commands;
 
 pub use text_files::load_text_file;
+pub use image_files::load_image_base64;
+pub use atomic_write::write_atomic;
+pub use file_names::{sanitize_file_name, unique_file_name};
 
 
 pub fn build_config_path<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<std::path::PathBuf, String> {
@@ -16,4 +22,81 @@ pub fn build_data_path<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<s
     .map_err(|e| format!("Failed to get document directory: {}", e))?;
 
   Ok(doc_dir.join("AiMindMap"))
-}
\ No newline at end of file
+}
+
+/// Whether a path is inside the data directory, after resolving `..` and symlinks
+/// Paths that don't exist are never inside
+pub fn is_in_data_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>, path: &std::path::Path) -> bool {
+  let Ok(data_dir) = build_data_path(app).and_then(|dir| dir.canonicalize().map_err(|e| e.to_string())) else {
+    return false;
+  };
+
+  path.canonicalize().is_ok_and(|path| path.starts_with(&data_dir))
+}
+
+/// Fingerprint of a file's content (FNV-1a), used to tell external edits from our own writes
+pub fn content_fingerprint(contents: &[u8]) -> u64 {
+  contents.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+    (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+  })
+}
+
+/// Resolve a mind map path as stored in the active file state.
+/// Absolute paths are used as is; relative names live in the data directory
+/// and are rejected if they would resolve outside of it.
+pub fn resolve_mind_map_path<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  path: &str
+) -> Result<std::path::PathBuf, String> {
+  let path = std::path::Path::new(path);
+
+  if path.is_absolute() {
+    return Ok(path.to_path_buf());
+  }
+
+  if !file_names::is_contained_relative_path(path) {
+    return Err(format!("Path is outside the data directory: {:?}", path));
+  }
+
+  Ok(build_data_path(app)?.join(path))
+}
+
+/// Turn a picked file path into the key used for the active file state.
+/// Files in the data directory keep their relative name so existing state and
+/// caches stay valid; anything else is tracked by its absolute path.
+pub fn mind_map_key<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  path: &std::path::Path
+) -> Result<String, String> {
+  if let Ok(data_dir) = build_data_path(app) {
+    if let Ok(relative) = path.strip_prefix(&data_dir) {
+      if let Some(name) = relative.to_str() {
+        return Ok(name.to_string());
+      }
+    }
+  }
+
+  path.to_str()
+    .map(|s| s.to_string())
+    .ok_or_else(|| format!("Invalid file path: {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fingerprint_matches_fnv1a() {
+    assert_eq!(content_fingerprint(b""), 0xcbf29ce484222325);
+    assert_eq!(content_fingerprint(b"a"), 0xaf63dc4c8601ec8c);
+  }
+
+  #[test]
+  fn fingerprint_tells_changed_content_apart() {
+    let saved = br#"{"name":"Plan","nodes":[]}"#;
+    let edited = br#"{"name":"Plan","nodes":[ ]}"#;
+
+    assert_eq!(content_fingerprint(saved), content_fingerprint(&saved.to_vec()));
+    assert_ne!(content_fingerprint(saved), content_fingerprint(edited));
+  }
+}
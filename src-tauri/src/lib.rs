@@ -2,7 +2,9 @@ mod ollama;
 mod active_file;
 mod app_menu;
 mod files;
+mod providers;
 mod state;
+mod vector_index;
 
 use tauri::{Builder, Manager};
 
@@ -21,32 +23,100 @@ pub fn run() {
           let mind_map_manager = active_file::initialize_mind_map_manager(app);
           app.manage(mind_map_manager);
 
+          // Load the vector index used for "find related nodes"
+          let vector_index = vector_index::initialize_vector_index(app);
+          app.manage(vector_index);
+
           app_menu::configure(app)?;
 
-          // Start Ollama health check background task (every 5 seconds)
+          // Start LLM provider health check background task (every 5 seconds)
           ollama::start_health_check_task(app.handle().clone(), 5);
 
+          // Start the autosave background task
+          active_file::start_autosave_task(app.handle().clone());
+
+          // Watch the active mind map for changes made by other programs
+          active_file::start_file_watcher(app.handle().clone());
+
           Ok(())
         })
-        .plugin(tauri_plugin_sql::Builder::new().build())
+        .plugin(
+            tauri_plugin_sql::Builder::new()
+                .add_migrations(active_file::DATABASE_URL, active_file::sql_plugin_migrations())
+                .build()
+        )
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .invoke_handler(tauri::generate_handler![
+            active_file::commands::apply_mind_map_recovery,
+            active_file::commands::clear_load_error,
             active_file::commands::create_mind_map,
+            active_file::commands::delete_mind_map,
+            active_file::commands::discard_mind_map_recovery,
+            active_file::commands::duplicate_mind_map,
             active_file::commands::flush_mind_map,
+            active_file::commands::get_autosave_interval,
+            active_file::commands::get_load_error,
             active_file::commands::get_mind_map,
             active_file::commands::get_save_state,
+            active_file::commands::get_storage_backend,
+            active_file::commands::list_mind_map_versions,
+            active_file::commands::list_mind_maps,
             active_file::commands::load_mind_map,
+            active_file::commands::migrate_mind_maps_to_sqlite,
             active_file::commands::open_file_dialog,
+            active_file::commands::preview_mind_map_version,
+            active_file::commands::recover_mind_map,
+            active_file::commands::rename_mind_map,
+            active_file::commands::repair_mind_map,
+            active_file::commands::resolve_flush_conflict,
+            active_file::commands::restore_mind_map_version,
             active_file::commands::save_mind_map,
+            active_file::commands::save_mind_map_as,
+            active_file::commands::set_autosave_interval,
+            active_file::commands::set_storage_backend,
             active_file::commands::update_edges,
             active_file::commands::update_nodes,
+            active_file::commands::update_ui_state,
+            active_file::commands::update_viewport,
+            active_file::commands::validate_mind_map,
+            files::commands::load_image_file,
             files::commands::load_txt_file,
             ollama::get_ollama_config,
+            ollama::embeddings::get_embedding_model,
+            ollama::embeddings::ollama_embed,
+            ollama::embeddings::set_embedding_model,
             ollama::get_ollama_status,
+            ollama::get_provider,
+            ollama::models::copy_ollama_model,
+            ollama::models::delete_ollama_model,
+            ollama::models::pull_ollama_model,
+            ollama::models::show_ollama_model,
             ollama::ollama_chat,
             ollama::ollama_generate,
-            ollama::set_ollama_config
+            ollama::options::get_all_model_options,
+            ollama::options::get_model_options,
+            ollama::options::set_model_options,
+            ollama::profiles::add_server_profile,
+            ollama::profiles::get_active_server_profile,
+            ollama::profiles::get_server_profile_statuses,
+            ollama::profiles::list_server_profiles,
+            ollama::profiles::remove_server_profile,
+            ollama::profiles::set_active_server_profile,
+            ollama::profiles::update_server_profile,
+            ollama::requests::cancel_llm_request,
+            ollama::requests::list_llm_requests,
+            ollama::set_ollama_config,
+            ollama::set_provider,
+            ollama::streaming::ollama_chat_stream,
+            ollama::streaming::ollama_generate_stream,
+            ollama::structured::ollama_chat_json,
+            ollama::structured::ollama_generate_json,
+            ollama::tools::list_builtin_tools,
+            ollama::tools::run_builtin_tool,
+            vector_index::commands::find_nodes_related_to,
+            vector_index::commands::find_related_nodes,
+            vector_index::commands::rebuild_vector_index
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,73 @@
+// Embeddings - calls the active provider's embedding endpoint
+use tauri::{AppHandle, Manager};
+
+use super::active_provider;
+use crate::state::AppState;
+
+/// Embed a batch of texts with the given model
+/// Returns one vector per input text, in the same order
+pub(crate) async fn embed_texts<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  model: &str,
+  texts: Vec<String>
+) -> Result<Vec<Vec<f32>>, String> {
+  if texts.is_empty() {
+    return Ok(vec![]);
+  }
+
+  let provider = active_provider(app)?;
+  let expected = texts.len();
+
+  let embeddings = provider.embed(model, texts).await?;
+
+  if embeddings.len() != expected {
+    return Err(format!(
+      "Provider returned {} embeddings for {} inputs",
+      embeddings.len(),
+      expected
+    ));
+  }
+
+  Ok(embeddings)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Embed one or more texts
+/// Uses the configured embedding model when `model` is omitted
+#[tauri::command]
+pub async fn ollama_embed(
+  app: AppHandle,
+  input: Vec<String>,
+  model: Option<String>
+) -> Result<Vec<Vec<f32>>, String> {
+  let model = model.unwrap_or_else(|| app.state::<AppState>().get_embedding_model());
+
+  embed_texts(&app, &model, input).await
+}
+
+/// Get the model used for embeddings and the vector index
+#[tauri::command]
+pub fn get_embedding_model(app: AppHandle) -> String {
+  app.state::<AppState>().get_embedding_model()
+}
+
+/// Change the embedding model and re-embed the vector index in the background
+#[tauri::command]
+pub fn set_embedding_model(app: AppHandle, model: String) {
+  let app_state = app.state::<AppState>();
+
+  if app_state.get_embedding_model() == model {
+    return;
+  }
+
+  app_state.set_embedding_model(&app, model);
+
+  tauri::async_runtime::spawn(async move {
+    if let Err(e) = crate::vector_index::rebuild_index(&app).await {
+      eprintln!("⚠️  Failed to rebuild vector index: {}", e);
+    }
+  });
+}
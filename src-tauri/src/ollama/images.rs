@@ -0,0 +1,36 @@
+// Image attachments for chat messages sent to vision models
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::files;
+
+/// An image attached to a chat message
+///
+/// Either a file path that the backend reads when the request is sent, or
+/// already encoded base64 data.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ImageAttachment {
+  Path {
+    path: String,
+  },
+  #[serde(rename_all = "camelCase")]
+  Base64 {
+    data: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+  },
+}
+
+impl ImageAttachment {
+  /// Get the image as base64 data and its mime type, reading it from disk if needed
+  pub fn to_base64(&self) -> Result<(String, String), String> {
+    match self {
+      ImageAttachment::Path { path } => files::load_image_base64(Path::new(path)),
+      ImageAttachment::Base64 { data, mime_type } => Ok((
+        data.clone(),
+        mime_type.clone().unwrap_or_else(|| "image/png".to_string()),
+      )),
+    }
+  }
+}
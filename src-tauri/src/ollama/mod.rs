@@ -0,0 +1,448 @@
+// LLM integration - configuration, LLM commands and health checks
+//
+// Commands keep their `ollama_` names for the frontend, but every call goes
+// through the configured `providers::LlmProvider` (Ollama or any
+// OpenAI-compatible server).
+//
+// This module is organized into several submodules:
+// - streaming: Token-by-token chat/generate commands that emit Tauri events
+// - requests: In-flight request registry and cancellation
+// - options: Per-request and per-model generation options
+// - models: Model management (pull, delete, show, copy) - Ollama only
+// - embeddings: Embedding generation
+// - profiles: Named server profiles and the active-profile selector
+// - tools: Tool definitions, built-in tools and the tool-call loop
+// - structured: JSON schema constrained output with validation and retries
+// - images: Image attachments for vision models
+
+pub mod embeddings;
+pub mod images;
+pub mod models;
+pub mod options;
+pub mod profiles;
+pub mod requests;
+pub mod streaming;
+pub mod structured;
+pub mod tools;
+
+use ollama_rs::Ollama;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tauri::Manager;
+use serde::{Deserialize, Serialize};
+
+use crate::providers::{build_provider, ChatRequest, GenerateRequest, LlmProvider, ProviderKind};
+use crate::state::AppState;
+use images::ImageAttachment;
+use options::{resolve_options, GenerationOptions};
+use profiles::ServerProfile;
+use requests::{resolve_request_id, run_tracked, LlmRequestKind};
+use tools::{resolve_builtin_tools, run_tool_loop, ToolCall, ToolDefinition};
+
+/// Configuration for connecting to the LLM server
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OllamaConfig {
+  pub domain: String,
+  pub port: u16,
+
+  /// Bearer token for OpenAI-compatible servers that require one
+  #[serde(rename = "apiKey", default, skip_serializing_if = "Option::is_none")]
+  pub api_key: Option<String>,
+}
+
+impl Default for OllamaConfig {
+  fn default() -> Self {
+    Self {
+      domain: "http://localhost".into(),
+      port: 11434,
+      api_key: None,
+    }
+  }
+}
+
+/// Represents a model available on the provider
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProviderModel {
+  pub name: String,
+  pub size: u64,
+  pub modified_at: String,
+}
+
+/// Runtime status of the LLM provider (not persisted to disk)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderStatus {
+  pub profile_id: String,
+  pub provider: ProviderKind,
+  pub is_available: bool,
+  pub models: Vec<ProviderModel>,
+  pub last_checked: String,
+  pub error_message: Option<String>,
+}
+
+impl ProviderStatus {
+  /// Status of a profile that has not been checked yet
+  pub fn for_profile(profile: &ServerProfile) -> Self {
+    Self {
+      profile_id: profile.id.clone(),
+      provider: profile.provider,
+      is_available: false,
+      models: vec![],
+      last_checked: chrono::Utc::now().to_rfc3339(),
+      error_message: None,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ChatMessage {
+  pub role: String,
+  pub content: String,
+
+  /// Images shown to vision models alongside the text
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub images: Vec<ImageAttachment>,
+
+  /// Tools the assistant wants to call (assistant messages only)
+  #[serde(rename = "toolCalls", default, skip_serializing_if = "Vec::is_empty")]
+  pub tool_calls: Vec<ToolCall>,
+
+  /// Id of the call a "tool" message answers (used by OpenAI-compatible servers)
+  #[serde(rename = "toolCallId", default, skip_serializing_if = "Option::is_none")]
+  pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+  pub fn assistant(content: String) -> Self {
+    Self {
+      role: "assistant".to_string(),
+      content,
+      ..Default::default()
+    }
+  }
+
+  /// The result of a tool call, to be sent back to the model
+  pub fn tool_result(call: &ToolCall, content: String) -> Self {
+    Self {
+      role: "tool".to_string(),
+      content,
+      tool_call_id: call.id.clone(),
+      ..Default::default()
+    }
+  }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_ollama_config(app: tauri::AppHandle) -> OllamaConfig {
+  let app_state = app.state::<AppState>();
+  app_state.get_ollama_config()
+}
+
+#[tauri::command]
+pub async fn set_ollama_config(
+  app: tauri::AppHandle,
+  config: OllamaConfig
+) {
+  let app_state = app.state::<AppState>();
+  app_state.set_ollama_config(&app, config);
+}
+
+/// Get the current provider status (availability and models)
+#[tauri::command]
+pub fn get_ollama_status(app: tauri::AppHandle) -> ProviderStatus {
+  let app_state = app.state::<AppState>();
+  app_state.get_provider_status()
+}
+
+#[tauri::command]
+pub fn get_provider(app: tauri::AppHandle) -> ProviderKind {
+  app.state::<AppState>().get_provider()
+}
+
+/// Switch the kind of server the connection points at
+/// The health check picks the change up on its next tick
+#[tauri::command]
+pub fn set_provider(app: tauri::AppHandle, provider: ProviderKind) {
+  app.state::<AppState>().set_provider(&app, provider);
+}
+
+/// Chat with Ollama and wait for the complete response
+///
+/// The call is tracked under `request_id` (generated when omitted) so it can
+/// be aborted with `cancel_llm_request`. `options` override the model's
+/// persisted defaults field by field.
+///
+/// `builtin_tools` names backend tools that are run automatically; calls to
+/// any tool in `tools` are returned in `toolCalls` for the frontend to run.
+#[tauri::command]
+pub async fn ollama_chat(
+  app: tauri::AppHandle,
+  model: String,
+  messages: Vec<ChatMessage>,
+  options: Option<GenerationOptions>,
+  request_id: Option<String>,
+  tools: Option<Vec<ToolDefinition>>,
+  builtin_tools: Option<Vec<String>>
+) -> Result<ChatMessage, String> {
+  println!("Ollama Chat Called");
+
+  let request_id = resolve_request_id(request_id);
+  let provider = active_provider(&app)?;
+  let builtin = resolve_builtin_tools(&builtin_tools.unwrap_or_default())?;
+
+  let mut all_tools = tools.unwrap_or_default();
+  all_tools.extend(builtin.iter().cloned());
+
+  let request = ChatRequest {
+    options: resolve_options(&app, &model, options),
+    model: model.clone(),
+    messages,
+    tools: all_tools,
+    format: None,
+  };
+
+  let loop_app = app.clone();
+
+  run_tracked(&app, request_id, LlmRequestKind::Chat, &model, async move {
+    run_tool_loop(&loop_app, provider, request, &builtin).await
+  }).await
+}
+
+/// Generate a completion using Ollama's generate API (non-chat mode)
+///
+/// This is useful for single-shot text generation without conversation history.
+/// Unlike chat mode, this doesn't maintain message history and is simpler for
+/// tasks like summarization, text generation, or one-off completions.
+#[tauri::command]
+pub async fn ollama_generate(
+  app: tauri::AppHandle,
+  model: String,
+  prompt: String,
+  options: Option<GenerationOptions>,
+  request_id: Option<String>
+) -> Result<String, String> {
+  println!("Ollama Generate Called");
+
+  let request_id = resolve_request_id(request_id);
+  let provider = active_provider(&app)?;
+
+  let request = GenerateRequest {
+    options: resolve_options(&app, &model, options),
+    model: model.clone(),
+    prompt,
+    format: None,
+  };
+
+  run_tracked(&app, request_id, LlmRequestKind::Generate, &model, async move {
+    provider.generate(request).await
+  }).await
+}
+
+// ============================================================================
+// Shared helpers
+// ============================================================================
+
+/// Build the provider for the current config
+/// Fails early if the last health check reported the server as unavailable
+pub(crate) fn active_provider<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>
+) -> Result<Arc<dyn LlmProvider>, String> {
+  let app_state = app.state::<AppState>();
+
+  // Check if the server is available first
+  let status = app_state.get_provider_status();
+  if !status.is_available {
+    return Err("LLM server is not available. Please check your connection settings.".to_string());
+  }
+
+  Ok(build_provider(app_state.get_provider(), &app_state.get_ollama_config()))
+}
+
+/// Build a native Ollama client for Ollama-only features (model management)
+pub(crate) fn build_client<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<Ollama, String> {
+  let app_state = app.state::<AppState>();
+
+  if app_state.get_provider() != ProviderKind::Ollama {
+    return Err("This feature is only available when connected to an Ollama server.".to_string());
+  }
+
+  // Check if Ollama is available first
+  if !app_state.get_provider_status().is_available {
+    return Err("Ollama server is not available. Please check your connection settings.".to_string());
+  }
+
+  // Pull Ollama API Base URL from config
+  let config = app_state.get_ollama_config();
+  Ok(Ollama::new(config.domain, config.port))
+}
+
+// ============================================================================
+// Background Health Check
+// ============================================================================
+
+pub const PROFILE_STATUS_EVENT: &str = "aiMindMap://ollama/profiles/status";
+
+/// Check one profile's availability and store its status
+/// Returns the new status
+pub async fn check_profile_health<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>,
+  profile: &ServerProfile
+) -> ProviderStatus {
+  let provider = build_provider(profile.provider, &profile.config);
+
+  let status = match provider.list_models().await {
+    Ok(models) => {
+      ProviderStatus {
+        profile_id: profile.id.clone(),
+        provider: profile.provider,
+        is_available: true,
+        models,
+        last_checked: chrono::Utc::now().to_rfc3339(),
+        error_message: None,
+      }
+    }
+    Err(e) => {
+      ProviderStatus {
+        profile_id: profile.id.clone(),
+        provider: profile.provider,
+        is_available: false,
+        models: vec![],
+        last_checked: chrono::Utc::now().to_rfc3339(),
+        error_message: Some(e),
+      }
+    }
+  };
+
+  // Update the app state
+  app.state::<AppState>().set_profile_status(status.clone());
+
+  status
+}
+
+/// Check every profile concurrently
+/// Returns the new statuses keyed by profile id
+pub async fn check_all_profiles<R: tauri::Runtime>(
+  app: &tauri::AppHandle<R>
+) -> HashMap<String, ProviderStatus> {
+  let checks: Vec<_> = app.state::<AppState>().get_profiles()
+    .into_iter()
+    .map(|profile| {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        check_profile_health(&app, &profile).await
+      })
+    })
+    .collect();
+
+  let mut statuses = HashMap::new();
+  for check in checks {
+    match check.await {
+      Ok(status) => {
+        statuses.insert(status.profile_id.clone(), status);
+      }
+      Err(e) => eprintln!("⚠️  Profile health check failed: {}", e),
+    }
+  }
+
+  statuses
+}
+
+/// Re-check the active profile immediately and push the new status to the frontend
+/// Used after operations that change the list of local models
+pub(crate) async fn refresh_provider_status<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> ProviderStatus {
+  use tauri::Emitter;
+
+  let profile = app.state::<AppState>().get_active_profile();
+  let status = check_profile_health(app, &profile).await;
+
+  if let Err(e) = app.emit("ollama-status-changed", &status) {
+    eprintln!("⚠️  Failed to emit ollama status event: {}", e);
+  }
+
+  status
+}
+
+/// Start the background health check task
+/// Checks every profile every `interval_secs` seconds and emits status events
+pub fn start_health_check_task<R: tauri::Runtime + 'static>(
+  app: tauri::AppHandle<R>,
+  interval_secs: u64
+) {
+  use std::time::Duration;
+  use tauri::Emitter;
+  use crate::app_menu;
+
+  tauri::async_runtime::spawn(async move {
+    // Small initial delay to let the app fully start
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+    let mut last_available: Option<(String, bool)> = None;
+
+    loop {
+      interval.tick().await;
+
+      let statuses = check_all_profiles(&app).await;
+      let status = app.state::<AppState>().get_provider_status();
+
+      // Emit status change events to frontend
+      if let Err(e) = app.emit("ollama-status-changed", &status) {
+        eprintln!("⚠️  Failed to emit ollama status event: {}", e);
+      }
+
+      if let Err(e) = app.emit(PROFILE_STATUS_EVENT, &statuses) {
+        eprintln!("⚠️  Failed to emit profile status event: {}", e);
+      }
+
+      // Update tray menu with current profiles and statuses
+      app_menu::update_tray_ollama_info(&app);
+
+      // Log only when the active profile's status changes
+      let current = (status.profile_id.clone(), status.is_available);
+      let availability_changed = last_available.as_ref() != Some(&current);
+      if availability_changed {
+        if status.is_available {
+          println!("✅ LLM server is available ({} models)", status.models.len());
+        } else {
+          println!("❌ LLM server is unavailable: {:?}", status.error_message);
+        }
+        last_available = Some(current);
+
+        // Update tray icon based on availability
+        update_tray_icon(&app, status.is_available);
+      }
+    }
+  });
+}
+
+/// Update the tray icon based on provider availability
+fn update_tray_icon<R: tauri::Runtime>(app: &tauri::AppHandle<R>, is_available: bool) {
+  use tauri::tray::TrayIconId;
+
+  // Load the appropriate icon using Tauri's icon loading
+  let icon = if is_available {
+    tauri::image::Image::from_bytes(include_bytes!("../../icons/32x32.png"))
+  } else {
+    tauri::image::Image::from_bytes(include_bytes!("../../icons/robot-dead/32x32.png"))
+  };
+
+  let icon = match icon {
+    Ok(img) => img,
+    Err(e) => {
+      eprintln!("⚠️  Failed to load tray icon: {}", e);
+      return;
+    }
+  };
+
+  // Get the tray icon and update it
+  if let Some(tray) = app.tray_by_id(&TrayIconId::new("main")) {
+    if let Err(e) = tray.set_icon(Some(icon)) {
+      eprintln!("⚠️  Failed to set tray icon: {}", e);
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,315 @@
+// Model management - pull, delete, show and copy local Ollama models
+//
+// Every operation goes through the configured OllamaConfig connection and
+// refreshes ProviderStatus.models afterwards so the UI sees the new model list.
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+use tokio_stream::StreamExt;
+
+use super::requests::{resolve_request_id, run_tracked, LlmRequestKind};
+use super::{build_client, refresh_provider_status};
+
+pub const PULL_PROGRESS_EVENT: &str = "aiMindMap://ollama/pull/progress";
+
+/// Progress update emitted while a model is being pulled
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PullProgressPayload {
+  pub request_id: String,
+  pub model: String,
+  pub status: String,
+  pub digest: Option<String>,
+  pub total: Option<u64>,
+  pub completed: Option<u64>,
+}
+
+/// Details about a local model, flattened from Ollama's show endpoint
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDetails {
+  pub name: String,
+  pub family: Option<String>,
+  pub parameter_count: Option<u64>,
+  pub context_length: Option<u64>,
+  pub parameters: String,
+  pub template: String,
+  pub license: String,
+  pub capabilities: Vec<String>,
+}
+
+/// One line of Ollama's pull progress, or the error that ended the pull
+#[derive(Debug, Deserialize)]
+struct PullStatus {
+  #[serde(default)]
+  status: String,
+  digest: Option<String>,
+  total: Option<u64>,
+  completed: Option<u64>,
+  error: Option<String>,
+}
+
+/// Splits the newline-delimited JSON of `/api/pull` into statuses
+///
+/// Bytes are buffered until a full line arrives, so statuses split across
+/// network reads or sharing one are all decoded.
+#[derive(Default)]
+struct PullStatusDecoder {
+  buffer: Vec<u8>,
+}
+
+impl PullStatusDecoder {
+  /// Feed the next read; returns the statuses of every line it completed
+  fn push(&mut self, bytes: &[u8]) -> Vec<PullStatus> {
+    self.buffer.extend_from_slice(bytes);
+    let mut statuses = Vec::new();
+
+    while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+      let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+      statuses.extend(Self::decode_line(&line));
+    }
+
+    statuses
+  }
+
+  /// Decode whatever is left once the response ended (a last line without newline)
+  fn finish(&mut self) -> Vec<PullStatus> {
+    let line = std::mem::take(&mut self.buffer);
+
+    Self::decode_line(&line).into_iter().collect()
+  }
+
+  fn decode_line(line: &[u8]) -> Option<PullStatus> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+
+    if line.is_empty() {
+      return None;
+    }
+
+    serde_json::from_str(line)
+      .inspect_err(|e| eprintln!("⚠️  Skipping unreadable pull status: {}", e))
+      .ok()
+  }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Pull a model from the Ollama library, emitting progress events
+///
+/// Tracked like any other LLM request so it can be aborted with
+/// `cancel_llm_request`.
+#[tauri::command]
+pub async fn pull_ollama_model(
+  app: AppHandle,
+  model: String,
+  request_id: Option<String>
+) -> Result<(), String> {
+  println!("⬇️  Pulling model: {}", model);
+
+  let request_id = resolve_request_id(request_id);
+  let ollama = build_client(&app)?;
+
+  let pull = {
+    let app = app.clone();
+    let request_id = request_id.clone();
+    let model = model.clone();
+
+    async move {
+      // ollama-rs decodes each network read as one status, which fails whenever a read
+      // holds several lines or half of one, so the progress stream is read here
+      let response = reqwest::Client::new()
+        .post(format!("{}api/pull", ollama.url_str()))
+        .json(&json!({ "name": model, "stream": true }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to pull {}: {}", model, e))?;
+
+      if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Failed to pull {} ({}): {}", model, status, body));
+      }
+
+      let mut bytes = response.bytes_stream();
+      let mut decoder = PullStatusDecoder::default();
+
+      // Ollama reports "success" last, a stream that ends before that was cut off
+      let mut last_status = None;
+
+      loop {
+        let (statuses, ended) = match bytes.next().await {
+          Some(Ok(bytes)) => (decoder.push(&bytes), false),
+          // Connection lost, timed out, ...
+          Some(Err(e)) => return Err(format!("Failed to pull {}: {}", model, e)),
+          None => (decoder.finish(), true),
+        };
+
+        for status in statuses {
+          // Errors reported by Ollama itself (e.g. unknown model)
+          if let Some(error) = status.error {
+            return Err(format!("Failed to pull {}: {}", model, error));
+          }
+
+          last_status = Some(status.status.clone());
+
+          let payload = PullProgressPayload {
+            request_id: request_id.clone(),
+            model: model.clone(),
+            status: status.status,
+            digest: status.digest,
+            total: status.total,
+            completed: status.completed,
+          };
+
+          if let Err(e) = app.emit(PULL_PROGRESS_EVENT, payload) {
+            eprintln!("⚠️  Failed to emit pull progress event: {}", e);
+          }
+        }
+
+        if ended {
+          break;
+        }
+      }
+
+      match last_status.as_deref() {
+        Some("success") => Ok(()),
+        Some(status) => Err(format!("Pull of {} ended before it completed (last status: {})", model, status)),
+        None => Err(format!("Pull of {} ended without reporting any progress", model)),
+      }
+    }
+  };
+
+  run_tracked(&app, request_id, LlmRequestKind::Pull, &model, pull).await?;
+
+  println!("✅ Model pulled: {}", model);
+  refresh_provider_status(&app).await;
+
+  Ok(())
+}
+
+/// Delete a local model
+#[tauri::command]
+pub async fn delete_ollama_model(app: AppHandle, model: String) -> Result<(), String> {
+  let ollama = build_client(&app)?;
+
+  ollama
+    .delete_model(model.clone())
+    .await
+    .map_err(|e| format!("Failed to delete {}: {}", model, e))?;
+
+  println!("🗑️  Model deleted: {}", model);
+  refresh_provider_status(&app).await;
+
+  Ok(())
+}
+
+/// Copy (tag) a local model under a new name
+#[tauri::command]
+pub async fn copy_ollama_model(
+  app: AppHandle,
+  source: String,
+  destination: String
+) -> Result<(), String> {
+  let ollama = build_client(&app)?;
+
+  ollama
+    .copy_model(source.clone(), destination.clone())
+    .await
+    .map_err(|e| format!("Failed to copy {} to {}: {}", source, destination, e))?;
+
+  println!("📋 Model copied: {} -> {}", source, destination);
+  refresh_provider_status(&app).await;
+
+  Ok(())
+}
+
+/// Show details (parameters, template, context length, family) of a local model
+#[tauri::command]
+pub async fn show_ollama_model(app: AppHandle, model: String) -> Result<ModelDetails, String> {
+  let ollama = build_client(&app)?;
+
+  let info = ollama
+    .show_model_info(model.clone())
+    .await
+    .map_err(|e| format!("Failed to show {}: {}", model, e))?;
+
+  // Architecture specific keys are prefixed with the family, e.g. "llama.context_length"
+  let family = info.model_info
+    .get("general.architecture")
+    .and_then(|v| v.as_str())
+    .map(|s| s.to_string());
+
+  let context_length = family
+    .as_ref()
+    .and_then(|family| info.model_info.get(&format!("{}.context_length", family)))
+    .and_then(|v| v.as_u64());
+
+  let parameter_count = info.model_info
+    .get("general.parameter_count")
+    .and_then(|v| v.as_u64());
+
+  Ok(ModelDetails {
+    name: model,
+    family,
+    parameter_count,
+    context_length,
+    parameters: info.parameters,
+    template: info.template,
+    license: info.license,
+    capabilities: info.capabilities,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn statuses(decoded: &[PullStatus]) -> Vec<&str> {
+    decoded.iter().map(|s| s.status.as_str()).collect()
+  }
+
+  #[test]
+  fn decodes_several_statuses_in_one_read() {
+    let mut decoder = PullStatusDecoder::default();
+
+    let decoded = decoder.push(b"{\"status\":\"writing manifest\"}\n{\"status\":\"success\"}\n");
+
+    assert_eq!(statuses(&decoded), ["writing manifest", "success"]);
+  }
+
+  #[test]
+  fn keeps_statuses_split_across_reads() {
+    let mut decoder = PullStatusDecoder::default();
+
+    assert!(decoder.push(b"{\"status\":\"pulling 6a0\",\"digest\":\"sha256:6a0\",\"to").is_empty());
+    let decoded = decoder.push(b"tal\":100,\"completed\":40}\n{\"status\":\"succ");
+
+    assert_eq!(statuses(&decoded), ["pulling 6a0"]);
+    assert_eq!((decoded[0].total, decoded[0].completed), (Some(100), Some(40)));
+    assert_eq!(decoded[0].digest.as_deref(), Some("sha256:6a0"));
+
+    assert_eq!(statuses(&decoder.push(b"ess\"}\n")), ["success"]);
+  }
+
+  #[test]
+  fn decodes_a_last_line_without_newline() {
+    let mut decoder = PullStatusDecoder::default();
+
+    assert!(decoder.push(b"{\"status\":\"success\"}").is_empty());
+    assert_eq!(statuses(&decoder.finish()), ["success"]);
+    assert!(decoder.finish().is_empty());
+  }
+
+  #[test]
+  fn reports_errors_and_skips_unreadable_lines() {
+    let mut decoder = PullStatusDecoder::default();
+
+    let decoded = decoder.push(b"not json\n\n{\"error\":\"pull model manifest: file does not exist\"}\n");
+
+    assert_eq!(decoded.len(), 1);
+    assert_eq!(decoded[0].error.as_deref(), Some("pull model manifest: file does not exist"));
+  }
+}
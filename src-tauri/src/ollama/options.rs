@@ -0,0 +1,129 @@
+// Generation options (temperature, top_p, num_ctx, seed, stop, ...)
+//
+// Options are resolved in two layers: persisted per-model defaults from
+// AppState, overridden field by field by the options sent with a request.
+use ollama_rs::models::ModelOptions;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+
+/// Subset of Ollama's model parameters that can be set per request or per model
+///
+/// Every field is optional; unset fields fall back to the next layer and
+/// ultimately to Ollama's own defaults.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationOptions {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub temperature: Option<f32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub top_p: Option<f32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub top_k: Option<u32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub num_ctx: Option<u64>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub num_predict: Option<i32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub repeat_penalty: Option<f32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub seed: Option<i32>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub stop: Option<Vec<String>>,
+}
+
+impl GenerationOptions {
+  /// True when no option is set
+  pub fn is_empty(&self) -> bool {
+    *self == Self::default()
+  }
+
+  /// Layer `overrides` on top of these options (overrides win per field)
+  pub fn merged_with(&self, overrides: &GenerationOptions) -> GenerationOptions {
+    GenerationOptions {
+      temperature: overrides.temperature.or(self.temperature),
+      top_p: overrides.top_p.or(self.top_p),
+      top_k: overrides.top_k.or(self.top_k),
+      num_ctx: overrides.num_ctx.or(self.num_ctx),
+      num_predict: overrides.num_predict.or(self.num_predict),
+      repeat_penalty: overrides.repeat_penalty.or(self.repeat_penalty),
+      seed: overrides.seed.or(self.seed),
+      stop: overrides.stop.clone().or_else(|| self.stop.clone()),
+    }
+  }
+
+  /// Convert to ollama-rs ModelOptions, or None if nothing is set
+  pub fn to_model_options(&self) -> Option<ModelOptions> {
+    if self.is_empty() {
+      return None;
+    }
+
+    let mut options = ModelOptions::default();
+
+    if let Some(temperature) = self.temperature {
+      options = options.temperature(temperature);
+    }
+    if let Some(top_p) = self.top_p {
+      options = options.top_p(top_p);
+    }
+    if let Some(top_k) = self.top_k {
+      options = options.top_k(top_k);
+    }
+    if let Some(num_ctx) = self.num_ctx {
+      options = options.num_ctx(num_ctx);
+    }
+    if let Some(num_predict) = self.num_predict {
+      options = options.num_predict(num_predict);
+    }
+    if let Some(repeat_penalty) = self.repeat_penalty {
+      options = options.repeat_penalty(repeat_penalty);
+    }
+    if let Some(seed) = self.seed {
+      options = options.seed(seed);
+    }
+    if let Some(stop) = &self.stop {
+      options = options.stop(stop.clone());
+    }
+
+    Some(options)
+  }
+}
+
+/// Resolve the effective options for a request against the model's defaults
+pub(crate) fn resolve_options<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  model: &str,
+  overrides: Option<GenerationOptions>
+) -> GenerationOptions {
+  let defaults = app.state::<AppState>().get_model_options(model);
+
+  match overrides {
+    Some(overrides) => defaults.merged_with(&overrides),
+    None => defaults,
+  }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Get the persisted default options for a model
+#[tauri::command]
+pub fn get_model_options(app: AppHandle, model: String) -> GenerationOptions {
+  app.state::<AppState>().get_model_options(&model)
+}
+
+/// Get the persisted default options for every model that has any
+#[tauri::command]
+pub fn get_all_model_options(app: AppHandle) -> HashMap<String, GenerationOptions> {
+  app.state::<AppState>().get_all_model_options()
+}
+
+/// Replace the persisted default options for a model
+/// Passing empty options removes the model's defaults
+#[tauri::command]
+pub fn set_model_options(app: AppHandle, model: String, options: GenerationOptions) {
+  app.state::<AppState>().set_model_options(&app, model, options);
+}
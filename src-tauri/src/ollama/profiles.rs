@@ -0,0 +1,126 @@
+// Server profiles - named LLM server connections with quick switching
+//
+// Every profile is health checked in the background; only the active profile
+// is used for LLM calls. Switching is available as commands and from the tray.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+
+use super::{refresh_provider_status, OllamaConfig, ProviderStatus};
+use crate::app_menu;
+use crate::providers::ProviderKind;
+use crate::state::AppState;
+
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+/// A named connection to an LLM server
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerProfile {
+  pub id: String,
+  pub name: String,
+  #[serde(default)]
+  pub provider: ProviderKind,
+  pub config: OllamaConfig,
+}
+
+impl Default for ServerProfile {
+  fn default() -> Self {
+    Self {
+      id: DEFAULT_PROFILE_ID.to_string(),
+      name: "Local Ollama".to_string(),
+      provider: ProviderKind::default(),
+      config: OllamaConfig::default(),
+    }
+  }
+}
+
+/// Make a profile active, re-check it and refresh the tray
+pub(crate) async fn activate_profile<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  id: &str
+) -> Result<ProviderStatus, String> {
+  app.state::<AppState>().set_active_profile(app, id)?;
+
+  println!("🔀 Switched to server profile: {}", id);
+
+  let status = refresh_provider_status(app).await;
+  app_menu::update_tray_ollama_info(app);
+
+  Ok(status)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn list_server_profiles(app: AppHandle) -> Vec<ServerProfile> {
+  app.state::<AppState>().get_profiles()
+}
+
+#[tauri::command]
+pub fn get_active_server_profile(app: AppHandle) -> ServerProfile {
+  app.state::<AppState>().get_active_profile()
+}
+
+/// Get the last health check result of every profile keyed by profile id
+#[tauri::command]
+pub fn get_server_profile_statuses(app: AppHandle) -> HashMap<String, ProviderStatus> {
+  app.state::<AppState>().get_profile_statuses()
+}
+
+/// Create a new profile with a generated id
+#[tauri::command]
+pub fn add_server_profile(
+  app: AppHandle,
+  name: String,
+  provider: ProviderKind,
+  config: OllamaConfig
+) -> ServerProfile {
+  let profile = ServerProfile {
+    id: uuid::Uuid::new_v4().to_string(),
+    name,
+    provider,
+    config,
+  };
+
+  app.state::<AppState>().add_profile(&app, profile.clone());
+  app_menu::update_tray_ollama_info(&app);
+
+  profile
+}
+
+/// Replace the name, provider and connection of an existing profile
+#[tauri::command]
+pub async fn update_server_profile(app: AppHandle, profile: ServerProfile) -> Result<(), String> {
+  let app_state = app.state::<AppState>();
+
+  if !app_state.update_profile(&app, profile.clone()) {
+    return Err(format!("Profile not found: {}", profile.id));
+  }
+
+  // Editing the active profile changes where LLM calls go, re-check right away
+  if app_state.get_active_profile_id() == profile.id {
+    refresh_provider_status(&app).await;
+  }
+
+  app_menu::update_tray_ollama_info(&app);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub fn remove_server_profile(app: AppHandle, id: String) -> Result<(), String> {
+  app.state::<AppState>().remove_profile(&app, &id)?;
+  app_menu::update_tray_ollama_info(&app);
+
+  Ok(())
+}
+
+/// Switch LLM calls to another profile
+/// Returns the freshly checked status of the new active profile
+#[tauri::command]
+pub async fn set_active_server_profile(app: AppHandle, id: String) -> Result<ProviderStatus, String> {
+  activate_profile(&app, &id).await
+}
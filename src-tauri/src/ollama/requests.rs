@@ -0,0 +1,133 @@
+// In-flight LLM request tracking and cancellation
+//
+// Every LLM call runs as its own task so that `cancel_llm_request` can abort
+// the underlying HTTP future. The registry of running requests lives in
+// AppState and is exposed to the frontend through `list_llm_requests`.
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::state::AppState;
+
+pub const REQUEST_CANCELLED_EVENT: &str = "aiMindMap://ollama/request/cancelled";
+
+/// The kind of LLM call a request was started by
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum LlmRequestKind {
+  Chat,
+  ChatStream,
+  Generate,
+  GenerateStream,
+  Pull,
+}
+
+/// Public description of an in-flight LLM request
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmRequestInfo {
+  pub request_id: String,
+  pub kind: LlmRequestKind,
+  pub model: String,
+  pub started_at: String,
+}
+
+/// Registry entry - the public info plus the handle used to abort the task
+pub(crate) struct ActiveLlmRequest {
+  pub(crate) info: LlmRequestInfo,
+  pub(crate) abort_handle: tokio::task::AbortHandle,
+}
+
+/// Payload emitted when a request is aborted
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestCancelledPayload {
+  pub request_id: String,
+}
+
+/// Use the frontend supplied id or generate a new one
+pub(crate) fn resolve_request_id(request_id: Option<String>) -> String {
+  request_id
+    .filter(|id| !id.is_empty())
+    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Run an LLM future as a tracked, abortable task
+///
+/// The request is registered in AppState for the duration of the call and
+/// removed afterwards regardless of the outcome.
+pub(crate) async fn run_tracked<R, T, F>(
+  app: &AppHandle<R>,
+  request_id: String,
+  kind: LlmRequestKind,
+  model: &str,
+  future: F
+) -> Result<T, String>
+where
+  R: tauri::Runtime,
+  T: Send + 'static,
+  F: Future<Output = Result<T, String>> + Send + 'static,
+{
+  let app_state = app.state::<AppState>();
+
+  // The task waits until it is registered, so a duplicate id never starts a call
+  let (start, started) = tokio::sync::oneshot::channel::<()>();
+
+  let handle = tauri::async_runtime::spawn(async move {
+    started.await.map_err(|_| "Request was not started".to_string())?;
+    future.await
+  });
+
+  let abort_handle = handle.inner().abort_handle();
+  let task_id = abort_handle.id();
+
+  if let Err(e) = app_state.try_register_llm_request(ActiveLlmRequest {
+    info: LlmRequestInfo {
+      request_id: request_id.clone(),
+      kind,
+      model: model.to_string(),
+      started_at: chrono::Utc::now().to_rfc3339(),
+    },
+    abort_handle,
+  }) {
+    handle.abort();
+    return Err(e);
+  }
+
+  let _ = start.send(());
+  let result = handle.await;
+
+  app_state.remove_llm_request(&request_id, task_id);
+
+  match result {
+    Ok(output) => output,
+    Err(tauri::Error::JoinError(e)) if e.is_cancelled() => {
+      println!("🛑 LLM request cancelled: {}", request_id);
+
+      let payload = RequestCancelledPayload { request_id: request_id.clone() };
+      if let Err(e) = app.emit(REQUEST_CANCELLED_EVENT, payload) {
+        eprintln!("⚠️  Failed to emit request cancelled event: {}", e);
+      }
+
+      Err(format!("Request {} was cancelled", request_id))
+    }
+    Err(e) => Err(format!("LLM request failed: {}", e)),
+  }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Abort a running LLM request
+/// Returns false if no request with that id is running
+#[tauri::command]
+pub fn cancel_llm_request(app: AppHandle, request_id: String) -> bool {
+  app.state::<AppState>().cancel_llm_request(&request_id)
+}
+
+/// List all LLM requests that are currently running
+#[tauri::command]
+pub fn list_llm_requests(app: AppHandle) -> Vec<LlmRequestInfo> {
+  app.state::<AppState>().list_llm_requests()
+}
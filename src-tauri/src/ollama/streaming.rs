@@ -0,0 +1,189 @@
+// Streaming variants of the chat/generate commands
+//
+// Partial tokens are pushed to the frontend as Tauri events keyed by a
+// frontend-supplied request id, followed by a single "done" event that
+// carries the full message and the generation stats reported by the provider.
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio_stream::StreamExt;
+
+use super::options::{resolve_options, GenerationOptions};
+use super::requests::{run_tracked, LlmRequestKind};
+use super::{active_provider, ChatMessage};
+use crate::providers::{ChatRequest, ChunkStream, GenerateRequest, GenerationStats};
+
+pub const STREAM_TOKEN_EVENT: &str = "aiMindMap://ollama/stream/token";
+pub const STREAM_DONE_EVENT: &str = "aiMindMap://ollama/stream/done";
+pub const STREAM_ERROR_EVENT: &str = "aiMindMap://ollama/stream/error";
+
+/// Payload for each partial token emitted while streaming
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamTokenPayload {
+  pub request_id: String,
+  pub content: String,
+}
+
+/// Payload for the final event of a stream
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamDonePayload {
+  pub request_id: String,
+  pub message: ChatMessage,
+  pub stats: Option<GenerationStats>,
+}
+
+/// Payload emitted when a stream fails part way through
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamErrorPayload {
+  pub request_id: String,
+  pub error: String,
+}
+
+/// Emit an error event for the request and hand the message back for `?` chaining
+fn emit_stream_error<R: tauri::Runtime>(app: &AppHandle<R>, request_id: &str, error: String) -> String {
+  let payload = StreamErrorPayload {
+    request_id: request_id.to_string(),
+    error: error.clone(),
+  };
+
+  if let Err(e) = app.emit(STREAM_ERROR_EVENT, payload) {
+    eprintln!("⚠️  Failed to emit stream error event: {}", e);
+  }
+
+  error
+}
+
+fn emit_token<R: tauri::Runtime>(app: &AppHandle<R>, request_id: &str, content: &str) {
+  let payload = StreamTokenPayload {
+    request_id: request_id.to_string(),
+    content: content.to_string(),
+  };
+
+  if let Err(e) = app.emit(STREAM_TOKEN_EVENT, payload) {
+    eprintln!("⚠️  Failed to emit stream token event: {}", e);
+  }
+}
+
+fn emit_done<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  request_id: &str,
+  message: &ChatMessage,
+  stats: Option<GenerationStats>
+) -> Result<(), String> {
+  let payload = StreamDonePayload {
+    request_id: request_id.to_string(),
+    message: message.clone(),
+    stats,
+  };
+
+  app.emit(STREAM_DONE_EVENT, payload)
+    .map_err(|e| format!("Failed to emit stream done event: {}", e))
+}
+
+/// Drive a chunk stream to completion, emitting token and done events
+/// Resolves with the assistant message assembled from all chunks
+async fn drive_stream<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  request_id: &str,
+  stream: Result<ChunkStream, String>
+) -> Result<ChatMessage, String> {
+  let mut stream = stream.map_err(|e| emit_stream_error(app, request_id, e))?;
+
+  let mut content = String::new();
+  let mut stats = None;
+
+  while let Some(chunk) = stream.next().await {
+    let chunk = chunk.map_err(|e| emit_stream_error(app, request_id, e))?;
+
+    if !chunk.content.is_empty() {
+      content.push_str(&chunk.content);
+      emit_token(app, request_id, &chunk.content);
+    }
+
+    if chunk.done {
+      stats = chunk.stats;
+      break;
+    }
+  }
+
+  let message = ChatMessage::assistant(content);
+
+  emit_done(app, request_id, &message, stats)?;
+
+  Ok(message)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Chat with the active provider and stream the response token by token
+///
+/// The frontend should subscribe to the stream events before invoking this
+/// command and filter them by `request_id`. The command resolves with the
+/// complete message once the stream is finished, and can be aborted with
+/// `cancel_llm_request`.
+#[tauri::command]
+pub async fn ollama_chat_stream(
+  app: AppHandle,
+  request_id: String,
+  model: String,
+  messages: Vec<ChatMessage>,
+  options: Option<GenerationOptions>
+) -> Result<ChatMessage, String> {
+  println!("Ollama Chat Stream Called ({})", request_id);
+
+  let provider = active_provider(&app)
+    .map_err(|e| emit_stream_error(&app, &request_id, e))?;
+
+  let request = ChatRequest {
+    options: resolve_options(&app, &model, options),
+    model: model.clone(),
+    messages,
+    tools: vec![],
+    format: None,
+  };
+
+  let stream_app = app.clone();
+  let stream_id = request_id.clone();
+
+  run_tracked(&app, request_id, LlmRequestKind::ChatStream, &model, async move {
+    drive_stream(&stream_app, &stream_id, provider.chat_stream(request).await).await
+  }).await
+}
+
+/// Generate a completion and stream the response token by token
+///
+/// Same event contract as `ollama_chat_stream`; the done event wraps the
+/// generated text in an assistant message.
+#[tauri::command]
+pub async fn ollama_generate_stream(
+  app: AppHandle,
+  request_id: String,
+  model: String,
+  prompt: String,
+  options: Option<GenerationOptions>
+) -> Result<String, String> {
+  println!("Ollama Generate Stream Called ({})", request_id);
+
+  let provider = active_provider(&app)
+    .map_err(|e| emit_stream_error(&app, &request_id, e))?;
+
+  let request = GenerateRequest {
+    options: resolve_options(&app, &model, options),
+    model: model.clone(),
+    prompt,
+    format: None,
+  };
+
+  let stream_app = app.clone();
+  let stream_id = request_id.clone();
+
+  run_tracked(&app, request_id, LlmRequestKind::GenerateStream, &model, async move {
+    drive_stream(&stream_app, &stream_id, provider.generate_stream(request).await)
+      .await
+      .map(|message| message.content)
+  }).await
+}
@@ -0,0 +1,176 @@
+// Structured output - JSON responses constrained and validated by a JSON schema
+//
+// The schema is sent as the provider's response format. Providers do not always
+// honour it, so every response is validated and the request is retried with the
+// validation errors as feedback until it passes or the retries are used up.
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::options::{resolve_options, GenerationOptions};
+use super::requests::{resolve_request_id, run_tracked, LlmRequestKind};
+use super::{active_provider, ChatMessage};
+use crate::providers::{ChatRequest, GenerateRequest};
+
+const DEFAULT_MAX_RETRIES: u32 = 2;
+
+fn default_max_retries() -> u32 {
+  DEFAULT_MAX_RETRIES
+}
+
+/// Schema the response has to match and how often to retry invalid output
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredOutput {
+  pub schema: Value,
+  #[serde(default = "default_max_retries")]
+  pub max_retries: u32,
+}
+
+/// Models sometimes wrap JSON in a markdown code fence despite the format
+fn strip_code_fence(text: &str) -> &str {
+  let trimmed = text.trim();
+
+  trimmed
+    .strip_prefix("```json")
+    .or_else(|| trimmed.strip_prefix("```"))
+    .and_then(|rest| rest.strip_suffix("```"))
+    .map(str::trim)
+    .unwrap_or(trimmed)
+}
+
+/// Parse a response and check it against the schema
+/// The error lists every violation so it can be fed back to the model
+fn parse_response(validator: &jsonschema::Validator, text: &str) -> Result<Value, String> {
+  let value: Value = serde_json::from_str(strip_code_fence(text))
+    .map_err(|e| format!("Response is not valid JSON: {}", e))?;
+
+  let errors: Vec<String> = validator
+    .iter_errors(&value)
+    .map(|e| format!("{} (at \"{}\")", e, e.instance_path))
+    .collect();
+
+  if !errors.is_empty() {
+    return Err(format!("Response does not match the schema: {}", errors.join("; ")));
+  }
+
+  Ok(value)
+}
+
+fn build_validator(schema: &Value) -> Result<jsonschema::Validator, String> {
+  jsonschema::validator_for(schema)
+    .map_err(|e| format!("Invalid JSON schema: {}", e))
+}
+
+fn feedback_message(error: &str) -> String {
+  format!(
+    "Your previous answer was rejected. {}. Reply again with only JSON that matches the schema.",
+    error
+  )
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Chat and return the response as JSON matching `output.schema`
+///
+/// Invalid responses are retried up to `output.maxRetries` times, each time
+/// telling the model what was wrong with its previous answer.
+#[tauri::command]
+pub async fn ollama_chat_json(
+  app: tauri::AppHandle,
+  model: String,
+  messages: Vec<ChatMessage>,
+  output: StructuredOutput,
+  options: Option<GenerationOptions>,
+  request_id: Option<String>
+) -> Result<Value, String> {
+  println!("Ollama Chat JSON Called");
+
+  let request_id = resolve_request_id(request_id);
+  let provider = active_provider(&app)?;
+  let validator = build_validator(&output.schema)?;
+
+  let mut request = ChatRequest {
+    options: resolve_options(&app, &model, options),
+    model: model.clone(),
+    messages,
+    tools: vec![],
+    format: Some(output.schema.clone()),
+  };
+
+  run_tracked(&app, request_id, LlmRequestKind::Chat, &model, async move {
+    let mut attempt = 0;
+
+    loop {
+      let response = provider.chat(request.clone()).await?;
+
+      match parse_response(&validator, &response.content) {
+        Ok(value) => return Ok(value),
+        Err(e) if attempt < output.max_retries => {
+          attempt += 1;
+          println!("⚠️  Structured output rejected, retrying ({}/{}): {}", attempt, output.max_retries, e);
+
+          request.messages.push(response);
+          request.messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: feedback_message(&e),
+            ..Default::default()
+          });
+        }
+        Err(e) => return Err(e),
+      }
+    }
+  }).await
+}
+
+/// Generate a completion and return it as JSON matching `output.schema`
+///
+/// Same retry behaviour as `ollama_chat_json`; the feedback is appended to
+/// the prompt since there is no conversation to continue.
+#[tauri::command]
+pub async fn ollama_generate_json(
+  app: tauri::AppHandle,
+  model: String,
+  prompt: String,
+  output: StructuredOutput,
+  options: Option<GenerationOptions>,
+  request_id: Option<String>
+) -> Result<Value, String> {
+  println!("Ollama Generate JSON Called");
+
+  let request_id = resolve_request_id(request_id);
+  let provider = active_provider(&app)?;
+  let validator = build_validator(&output.schema)?;
+
+  let mut request = GenerateRequest {
+    options: resolve_options(&app, &model, options),
+    model: model.clone(),
+    prompt: prompt.clone(),
+    format: Some(output.schema.clone()),
+  };
+
+  run_tracked(&app, request_id, LlmRequestKind::Generate, &model, async move {
+    let mut attempt = 0;
+
+    loop {
+      let response = provider.generate(request.clone()).await?;
+
+      match parse_response(&validator, &response) {
+        Ok(value) => return Ok(value),
+        Err(e) if attempt < output.max_retries => {
+          attempt += 1;
+          println!("⚠️  Structured output rejected, retrying ({}/{}): {}", attempt, output.max_retries, e);
+
+          request.prompt = format!(
+            "{}\n\nPrevious answer:\n{}\n\n{}",
+            prompt,
+            response,
+            feedback_message(&e)
+          );
+        }
+        Err(e) => return Err(e),
+      }
+    }
+  }).await
+}
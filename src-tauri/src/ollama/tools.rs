@@ -0,0 +1,257 @@
+// Tool calling - tool definitions, the built-in tool registry and the chat tool loop
+//
+// Tools declared by the frontend are handed back as tool calls for the frontend
+// to run. Built-in tools are run here, and their results are fed back to the
+// model until it answers or `MAX_TOOL_ITERATIONS` is reached.
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use super::ChatMessage;
+use crate::active_file::commands::emit_state_update;
+use crate::active_file::nodes::{BaseNodeData, FlowNode, MindMapNode, Position};
+use crate::active_file::MindMapManager;
+use crate::files;
+use crate::providers::{ChatRequest, LlmProvider};
+
+/// Upper bound on model round trips in one chat call
+pub const MAX_TOOL_ITERATIONS: usize = 5;
+
+/// Longest file content handed to the model by `read_file`
+const MAX_READ_FILE_CHARS: usize = 20_000;
+
+const READ_FILE_TOOL: &str = "read_file";
+const SEARCH_MIND_MAPS_TOOL: &str = "search_mind_maps";
+const CREATE_CHILD_NODE_TOOL: &str = "create_child_node";
+
+/// A tool the model may call, `parameters` is a JSON schema object
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolDefinition {
+  pub name: String,
+  pub description: String,
+  pub parameters: Value,
+}
+
+/// A tool call requested by the model
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCall {
+  /// Call id assigned by OpenAI-compatible servers (Ollama does not use ids)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub id: Option<String>,
+  pub name: String,
+  #[serde(default)]
+  pub arguments: Value,
+}
+
+/// Definitions of every tool the backend can run itself
+pub fn builtin_tools() -> Vec<ToolDefinition> {
+  vec![
+    ToolDefinition {
+      name: READ_FILE_TOOL.to_string(),
+      description: "Read a text file attached to a file node of the open mind map, or from the mind map directory, and return its contents".to_string(),
+      parameters: json!({
+        "type": "object",
+        "properties": {
+          "path": { "type": "string", "description": "Absolute path of the file" }
+        },
+        "required": ["path"]
+      }),
+    },
+    ToolDefinition {
+      name: SEARCH_MIND_MAPS_TOOL.to_string(),
+      description: "Search the nodes of all saved mind maps for content related to a query".to_string(),
+      parameters: json!({
+        "type": "object",
+        "properties": {
+          "query": { "type": "string", "description": "What to search for" },
+          "limit": { "type": "integer", "description": "Maximum number of results (default 5)" }
+        },
+        "required": ["query"]
+      }),
+    },
+    ToolDefinition {
+      name: CREATE_CHILD_NODE_TOOL.to_string(),
+      description: "Add a text node below an existing node of the open mind map".to_string(),
+      parameters: json!({
+        "type": "object",
+        "properties": {
+          "parentId": { "type": "string", "description": "Id of the node to attach the new node to" },
+          "content": { "type": "string", "description": "Text content of the new node" }
+        },
+        "required": ["parentId", "content"]
+      }),
+    },
+  ]
+}
+
+/// Resolve built-in tool names to their definitions, rejecting unknown names
+pub(crate) fn resolve_builtin_tools(names: &[String]) -> Result<Vec<ToolDefinition>, String> {
+  let available = builtin_tools();
+
+  names
+    .iter()
+    .map(|name| {
+      available.iter()
+        .find(|tool| &tool.name == name)
+        .cloned()
+        .ok_or_else(|| format!("Unknown built-in tool: {}", name))
+    })
+    .collect()
+}
+
+fn string_argument<'a>(call: &'a ToolCall, name: &str) -> Result<&'a str, String> {
+  call.arguments
+    .get(name)
+    .and_then(|v| v.as_str())
+    .ok_or_else(|| format!("Tool {} is missing the \"{}\" argument", call.name, name))
+}
+
+/// Files the model may read: those in the data directory and those attached to
+/// file nodes of the open mind map, so a prompt can't make it read anything else
+fn is_readable_by_tools<R: tauri::Runtime>(app: &AppHandle<R>, path: &Path) -> bool {
+  if files::is_in_data_dir(app, path) {
+    return true;
+  }
+
+  let Ok(path) = path.canonicalize() else {
+    return false;
+  };
+
+  app.state::<MindMapManager>()
+    .get_active_mind_map()
+    .nodes
+    .iter()
+    .filter_map(|node| match node {
+      MindMapNode::File(node) => node.data.file.as_deref(),
+      _ => None,
+    })
+    .any(|file| Path::new(file).canonicalize().is_ok_and(|file| file == path))
+}
+
+fn read_file<R: tauri::Runtime>(app: &AppHandle<R>, call: &ToolCall) -> Result<String, String> {
+  let path = Path::new(string_argument(call, "path")?);
+
+  if !is_readable_by_tools(app, path) {
+    return Err(format!(
+      "Access denied: {:?} is neither in the mind map directory nor attached to a file node",
+      path
+    ));
+  }
+
+  let content = files::load_text_file(path)?;
+
+  if content.chars().count() > MAX_READ_FILE_CHARS {
+    let truncated: String = content.chars().take(MAX_READ_FILE_CHARS).collect();
+    return Ok(format!("{}\n[truncated]", truncated));
+  }
+
+  Ok(content)
+}
+
+async fn search_mind_maps<R: tauri::Runtime>(app: &AppHandle<R>, call: &ToolCall) -> Result<String, String> {
+  let query = string_argument(call, "query")?;
+  let limit = call.arguments
+    .get("limit")
+    .and_then(|v| v.as_u64())
+    .unwrap_or(5) as usize;
+
+  let hits = crate::vector_index::search_text(app, query, limit).await?;
+
+  serde_json::to_string(&hits)
+    .map_err(|e| format!("Failed to serialize search results: {}", e))
+}
+
+fn create_child_node<R: tauri::Runtime>(app: &AppHandle<R>, call: &ToolCall) -> Result<String, String> {
+  let parent_id = string_argument(call, "parentId")?;
+  let content = string_argument(call, "content")?;
+
+  let manager = app.state::<MindMapManager>();
+
+  // Place the child below the parent, next to any existing children
+  let node_id = manager.append_child_node(parent_id, |parent, siblings| {
+    let Position { x, y } = parent.position();
+
+    MindMapNode::Text(FlowNode::new(
+      uuid::Uuid::new_v4().to_string(),
+      Position { x: x + siblings as f64 * 320.0, y: y + 240.0 },
+      BaseNodeData { content: Some(content.to_string()), ..Default::default() },
+    ))
+  })?;
+
+  emit_state_update(app, &manager.get_active_mind_map())?;
+
+  println!("🛠️  Tool created node {} under {}", node_id, parent_id);
+
+  Ok(json!({ "nodeId": node_id }).to_string())
+}
+
+/// Run a built-in tool and return its result as text for the model
+pub(crate) async fn execute_builtin_tool<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  call: &ToolCall
+) -> Result<String, String> {
+  match call.name.as_str() {
+    READ_FILE_TOOL => read_file(app, call),
+    SEARCH_MIND_MAPS_TOOL => search_mind_maps(app, call).await,
+    CREATE_CHILD_NODE_TOOL => create_child_node(app, call),
+    _ => Err(format!("Unknown built-in tool: {}", call.name)),
+  }
+}
+
+/// Chat, running enabled built-in tool calls until the model answers
+///
+/// Returns early with the assistant message when the model calls a tool that
+/// is not an enabled built-in, so the frontend can run it and continue.
+pub(crate) async fn run_tool_loop<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  provider: Arc<dyn LlmProvider>,
+  mut request: ChatRequest,
+  builtin: &[ToolDefinition]
+) -> Result<ChatMessage, String> {
+  for _ in 0..MAX_TOOL_ITERATIONS {
+    let message = provider.chat(request.clone()).await?;
+
+    let all_builtin = message.tool_calls
+      .iter()
+      .all(|call| builtin.iter().any(|tool| tool.name == call.name));
+
+    if message.tool_calls.is_empty() || !all_builtin {
+      return Ok(message);
+    }
+
+    request.messages.push(message.clone());
+
+    for call in &message.tool_calls {
+      println!("🛠️  Running tool: {}", call.name);
+
+      // Failures are reported to the model so it can recover
+      let result = execute_builtin_tool(app, call)
+        .await
+        .unwrap_or_else(|e| format!("Error: {}", e));
+
+      request.messages.push(ChatMessage::tool_result(call, result));
+    }
+  }
+
+  Err(format!("Model was still calling tools after {} iterations", MAX_TOOL_ITERATIONS))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List the tools the backend can run itself
+#[tauri::command]
+pub fn list_builtin_tools() -> Vec<ToolDefinition> {
+  builtin_tools()
+}
+
+/// Run a built-in tool call the frontend received from `ollama_chat`
+#[tauri::command]
+pub async fn run_builtin_tool(app: AppHandle, call: ToolCall) -> Result<String, String> {
+  execute_builtin_tool(&app, &call).await
+}
@@ -0,0 +1,105 @@
+// LLM providers - abstraction over the servers that answer chat/generate calls
+//
+// The Tauri commands in `ollama` only talk to the `LlmProvider` trait, so the
+// app can point at any backend that has an implementation here:
+// - ollama: Native Ollama API via ollama-rs
+// - openai: OpenAI-compatible HTTP API (llama.cpp server, LM Studio, vLLM, ...)
+
+mod ollama;
+mod openai;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::ollama::options::GenerationOptions;
+use crate::ollama::tools::ToolDefinition;
+use crate::ollama::{ChatMessage, OllamaConfig, ProviderModel};
+
+/// Which kind of server the connection points at
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderKind {
+  #[default]
+  Ollama,
+  OpenAiCompatible,
+}
+
+/// Timing and token statistics reported with a finished response
+///
+/// Providers that only report token usage leave the durations at zero.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationStats {
+  pub total_duration: u64,
+  pub load_duration: u64,
+  pub prompt_eval_count: u64,
+  pub prompt_eval_duration: u64,
+  pub eval_count: u64,
+  pub eval_duration: u64,
+}
+
+/// A chat request in provider independent form
+#[derive(Debug, Clone)]
+pub struct ChatRequest {
+  pub model: String,
+  pub messages: Vec<ChatMessage>,
+  pub options: GenerationOptions,
+  /// Tools the model may call (ignored by streaming calls)
+  pub tools: Vec<ToolDefinition>,
+  /// JSON schema the response has to follow
+  pub format: Option<Value>,
+}
+
+/// A single-shot completion request in provider independent form
+#[derive(Debug, Clone)]
+pub struct GenerateRequest {
+  pub model: String,
+  pub prompt: String,
+  pub options: GenerationOptions,
+  /// JSON schema the response has to follow
+  pub format: Option<Value>,
+}
+
+/// One piece of a streamed response
+#[derive(Debug, Clone, Default)]
+pub struct StreamChunk {
+  pub content: String,
+  pub done: bool,
+  pub stats: Option<GenerationStats>,
+}
+
+/// A stream of response pieces, ending with a chunk that has `done` set
+pub type ChunkStream = Pin<Box<dyn tokio_stream::Stream<Item = Result<StreamChunk, String>> + Send>>;
+
+/// Operations every LLM backend has to support
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+  /// List the models the server can serve (used by the health check)
+  async fn list_models(&self) -> Result<Vec<ProviderModel>, String>;
+
+  /// Chat and wait for the complete response, including any tool calls
+  async fn chat(&self, request: ChatRequest) -> Result<ChatMessage, String>;
+
+  /// Chat and stream the response
+  async fn chat_stream(&self, request: ChatRequest) -> Result<ChunkStream, String>;
+
+  /// Single-shot completion
+  async fn generate(&self, request: GenerateRequest) -> Result<String, String>;
+
+  /// Single-shot completion, streamed
+  async fn generate_stream(&self, request: GenerateRequest) -> Result<ChunkStream, String>;
+
+  /// Embed a batch of texts, one vector per input in the same order
+  async fn embed(&self, model: &str, input: Vec<String>) -> Result<Vec<Vec<f32>>, String>;
+}
+
+/// Build the provider for a connection
+pub fn build_provider(kind: ProviderKind, config: &OllamaConfig) -> Arc<dyn LlmProvider> {
+  match kind {
+    ProviderKind::Ollama => Arc::new(ollama::OllamaProvider::new(config)),
+    ProviderKind::OpenAiCompatible => Arc::new(openai::OpenAiProvider::new(config)),
+  }
+}
@@ -0,0 +1,233 @@
+// Ollama provider - native API via ollama-rs
+//
+// Documentation: https://crates.io/crates/ollama-rs
+use async_trait::async_trait;
+use ollama_rs::generation::chat::request::ChatMessageRequest;
+use ollama_rs::generation::chat::ChatMessage as OllamaChatMessage;
+use ollama_rs::generation::completion::request::GenerationRequest;
+use ollama_rs::generation::embeddings::request::{EmbeddingsInput, GenerateEmbeddingsRequest};
+use ollama_rs::generation::images::Image;
+use ollama_rs::generation::parameters::{FormatType, JsonStructure};
+use ollama_rs::generation::tools::{ToolCall as OllamaToolCall, ToolCallFunction, ToolInfo};
+use ollama_rs::Ollama;
+use serde_json::{json, Value};
+use tokio_stream::StreamExt;
+
+use super::{ChatRequest, ChunkStream, GenerateRequest, GenerationStats, LlmProvider, StreamChunk};
+use crate::ollama::tools::{ToolCall, ToolDefinition};
+use crate::ollama::{ChatMessage, OllamaConfig, ProviderModel};
+
+pub struct OllamaProvider {
+  client: Ollama,
+}
+
+impl OllamaProvider {
+  pub fn new(config: &OllamaConfig) -> Self {
+    Self {
+      client: Ollama::new(config.domain.clone(), config.port),
+    }
+  }
+}
+
+/// Convert ChatMessage to ollama-rs ChatMessage format
+/// Fails if an attached image cannot be read
+fn to_ollama_messages(messages: &[ChatMessage]) -> Result<Vec<OllamaChatMessage>, String> {
+  messages
+    .iter()
+    .map(|msg| {
+      let mut message = match msg.role.as_str() {
+        "user" => OllamaChatMessage::user(msg.content.clone()),
+        "assistant" => OllamaChatMessage::assistant(msg.content.clone()),
+        "system" => OllamaChatMessage::system(msg.content.clone()),
+        "tool" => OllamaChatMessage::tool(msg.content.clone()),
+        _ => OllamaChatMessage::user(msg.content.clone()), // default to user
+      };
+
+      message.tool_calls = msg.tool_calls
+        .iter()
+        .map(|call| OllamaToolCall {
+          function: ToolCallFunction {
+            name: call.name.clone(),
+            arguments: call.arguments.clone(),
+          },
+        })
+        .collect();
+
+      if !msg.images.is_empty() {
+        let images = msg.images
+          .iter()
+          .map(|image| image.to_base64().map(|(data, _)| Image::from_base64(data)))
+          .collect::<Result<Vec<_>, _>>()?;
+
+        message = message.with_images(images);
+      }
+
+      Ok(message)
+    })
+    .collect()
+}
+
+/// Convert a tool definition to the ollama-rs format
+/// ToolInfo only has a typed constructor, so it is built from its JSON form
+fn to_tool_info(tool: &ToolDefinition) -> Result<ToolInfo, String> {
+  serde_json::from_value(json!({
+    "type": "Function",
+    "function": {
+      "name": tool.name,
+      "description": tool.description,
+      "parameters": tool.parameters,
+    }
+  }))
+  .map_err(|e| format!("Invalid tool definition \"{}\": {}", tool.name, e))
+}
+
+/// Convert a JSON schema to Ollama's structured `format`
+fn to_format(schema: Value) -> Result<FormatType, String> {
+  let schema = schemars::Schema::try_from(schema)
+    .map_err(|e| format!("Invalid JSON schema: {}", e))?;
+
+  Ok(FormatType::StructuredJson(Box::new(JsonStructure::new_for_schema(schema))))
+}
+
+fn build_chat_request(request: ChatRequest) -> Result<ChatMessageRequest, String> {
+  let tools = request.tools
+    .iter()
+    .map(to_tool_info)
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let mut chat_request = ChatMessageRequest::new(request.model, to_ollama_messages(&request.messages)?);
+  chat_request.options = request.options.to_model_options();
+  chat_request.tools = tools;
+  chat_request.format = request.format.map(to_format).transpose()?;
+  Ok(chat_request)
+}
+
+fn build_generation_request(request: GenerateRequest) -> Result<GenerationRequest<'static>, String> {
+  let mut generation_request = GenerationRequest::new(request.model, request.prompt);
+  generation_request.options = request.options.to_model_options();
+  generation_request.format = request.format.map(to_format).transpose()?;
+  Ok(generation_request)
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+  async fn list_models(&self) -> Result<Vec<ProviderModel>, String> {
+    let models = self.client
+      .list_local_models()
+      .await
+      .map_err(|e| format!("{}", e))?;
+
+    Ok(models
+      .into_iter()
+      .map(|m| ProviderModel {
+        name: m.name,
+        size: m.size,
+        modified_at: m.modified_at,
+      })
+      .collect())
+  }
+
+  async fn chat(&self, request: ChatRequest) -> Result<ChatMessage, String> {
+    let response = self.client
+      .send_chat_messages(build_chat_request(request)?)
+      .await
+      .map_err(|e| format!("Ollama API error: {}", e))?;
+
+    let mut message = ChatMessage::assistant(response.message.content);
+    message.tool_calls = response.message.tool_calls
+      .into_iter()
+      .map(|call| ToolCall {
+        id: None,
+        name: call.function.name,
+        arguments: call.function.arguments,
+      })
+      .collect();
+
+    Ok(message)
+  }
+
+  async fn chat_stream(&self, request: ChatRequest) -> Result<ChunkStream, String> {
+    let stream = self.client
+      .send_chat_messages_stream(build_chat_request(request)?)
+      .await
+      .map_err(|e| format!("Ollama API error: {}", e))?;
+
+    let chunks = stream.map(|chunk| {
+      let chunk = chunk.map_err(|_| "Ollama stream was interrupted".to_string())?;
+
+      Ok(StreamChunk {
+        content: chunk.message.content,
+        done: chunk.done,
+        stats: chunk.final_data.map(|data| GenerationStats {
+          total_duration: data.total_duration,
+          load_duration: data.load_duration,
+          prompt_eval_count: data.prompt_eval_count,
+          prompt_eval_duration: data.prompt_eval_duration,
+          eval_count: data.eval_count,
+          eval_duration: data.eval_duration,
+        }),
+      })
+    });
+
+    Ok(Box::pin(chunks))
+  }
+
+  async fn generate(&self, request: GenerateRequest) -> Result<String, String> {
+    let response = self.client
+      .generate(build_generation_request(request)?)
+      .await
+      .map_err(|e| format!("Ollama API error: {}", e))?;
+
+    Ok(response.response)
+  }
+
+  async fn generate_stream(&self, request: GenerateRequest) -> Result<ChunkStream, String> {
+    let mut stream = self.client
+      .generate_stream(build_generation_request(request)?)
+      .await
+      .map_err(|e| format!("Ollama API error: {}", e))?;
+
+    // Each item from ollama-rs is a batch of responses decoded from one HTTP chunk
+    let chunks = async_stream::stream! {
+      while let Some(batch) = stream.next().await {
+        let batch = match batch {
+          Ok(batch) => batch,
+          Err(e) => {
+            yield Err(format!("Ollama API error: {}", e));
+            break;
+          }
+        };
+
+        for response in batch {
+          let stats = response.done.then(|| GenerationStats {
+            total_duration: response.total_duration.unwrap_or_default(),
+            load_duration: response.load_duration.unwrap_or_default(),
+            prompt_eval_count: response.prompt_eval_count.unwrap_or_default(),
+            prompt_eval_duration: response.prompt_eval_duration.unwrap_or_default(),
+            eval_count: response.eval_count.unwrap_or_default(),
+            eval_duration: response.eval_duration.unwrap_or_default(),
+          });
+
+          yield Ok(StreamChunk {
+            content: response.response,
+            done: response.done,
+            stats,
+          });
+        }
+      }
+    };
+
+    Ok(Box::pin(chunks))
+  }
+
+  async fn embed(&self, model: &str, input: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+    let request = GenerateEmbeddingsRequest::new(model.to_string(), EmbeddingsInput::Multiple(input));
+
+    let response = self.client
+      .generate_embeddings(request)
+      .await
+      .map_err(|e| format!("Ollama API error: {}", e))?;
+
+    Ok(response.embeddings)
+  }
+}
@@ -0,0 +1,584 @@
+// OpenAI-compatible provider - llama.cpp server, LM Studio, vLLM, ...
+//
+// Talks to the `/v1` REST API. Only the sampling options that are part of the
+// OpenAI API (plus the widely supported `top_k`) are forwarded; Ollama-only
+// options such as `num_ctx` are configured on the server instead.
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tokio_stream::StreamExt;
+
+use super::{ChatRequest, ChunkStream, GenerateRequest, GenerationStats, LlmProvider, StreamChunk};
+use crate::ollama::options::GenerationOptions;
+use crate::ollama::tools::ToolCall;
+use crate::ollama::{ChatMessage, OllamaConfig, ProviderModel};
+
+pub struct OpenAiProvider {
+  client: reqwest::Client,
+  base_url: String,
+  api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+  data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+  id: String,
+  #[serde(default)]
+  created: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct Usage {
+  #[serde(default)]
+  prompt_tokens: u64,
+  #[serde(default)]
+  completion_tokens: u64,
+}
+
+impl From<Usage> for GenerationStats {
+  fn from(usage: Usage) -> Self {
+    GenerationStats {
+      prompt_eval_count: usage.prompt_tokens,
+      eval_count: usage.completion_tokens,
+      ..Default::default()
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct EmbeddingList {
+  data: Vec<EmbeddingEntry>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingEntry {
+  index: usize,
+  embedding: Vec<f32>,
+}
+
+impl OpenAiProvider {
+  pub fn new(config: &OllamaConfig) -> Self {
+    Self {
+      client: reqwest::Client::new(),
+      base_url: format!("{}:{}/v1", config.domain.trim_end_matches('/'), config.port),
+      api_key: config.api_key.clone().filter(|key| !key.is_empty()),
+    }
+  }
+
+  fn get(&self, path: &str) -> reqwest::RequestBuilder {
+    self.authorize(self.client.get(format!("{}{}", self.base_url, path)))
+  }
+
+  fn post(&self, path: &str, body: &Value) -> reqwest::RequestBuilder {
+    self.authorize(self.client.post(format!("{}{}", self.base_url, path)).json(body))
+  }
+
+  fn authorize(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match &self.api_key {
+      Some(key) => builder.bearer_auth(key),
+      None => builder,
+    }
+  }
+
+  /// Send a request and return the response if the status is a success
+  async fn send(builder: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+    let response = builder
+      .send()
+      .await
+      .map_err(|e| format!("OpenAI-compatible API error: {}", e))?;
+
+    Self::check_status(response).await
+  }
+
+  /// Start a streaming request
+  /// Servers that reject `stream_options` are asked again without it (and report no usage)
+  async fn send_stream(&self, path: &str, mut body: Value) -> Result<reqwest::Response, String> {
+    let response = self.post(path, &body)
+      .send()
+      .await
+      .map_err(|e| format!("OpenAI-compatible API error: {}", e))?;
+
+    let rejected = matches!(
+      response.status(),
+      reqwest::StatusCode::BAD_REQUEST | reqwest::StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    match body.as_object_mut().and_then(|body| body.remove("stream_options")) {
+      Some(_) if rejected => {
+        eprintln!("⚠️  Server rejected stream_options, streaming without usage stats");
+        Self::send(self.post(path, &body)).await
+      }
+      _ => Self::check_status(response).await,
+    }
+  }
+
+  /// Pass a response on if its status is a success, otherwise turn its body into an error
+  async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    if !response.status().is_success() {
+      let status = response.status();
+      let body = response.text().await.unwrap_or_default();
+      return Err(format!("OpenAI-compatible API error ({}): {}", status, body));
+    }
+
+    Ok(response)
+  }
+
+  async fn send_json(builder: reqwest::RequestBuilder) -> Result<Value, String> {
+    Self::send(builder)
+      .await?
+      .json::<Value>()
+      .await
+      .map_err(|e| format!("Invalid response from OpenAI-compatible API: {}", e))
+  }
+}
+
+/// Map generation options onto OpenAI request fields
+fn sampling_params(options: &GenerationOptions) -> Map<String, Value> {
+  let mut params = Map::new();
+
+  if let Some(temperature) = options.temperature {
+    params.insert("temperature".into(), json!(temperature));
+  }
+  if let Some(top_p) = options.top_p {
+    params.insert("top_p".into(), json!(top_p));
+  }
+  if let Some(top_k) = options.top_k {
+    params.insert("top_k".into(), json!(top_k));
+  }
+  if let Some(num_predict) = options.num_predict {
+    params.insert("max_tokens".into(), json!(num_predict));
+  }
+  if let Some(seed) = options.seed {
+    params.insert("seed".into(), json!(seed));
+  }
+  if let Some(stop) = &options.stop {
+    params.insert("stop".into(), json!(stop));
+  }
+
+  params
+}
+
+/// Request a JSON schema constrained response
+fn response_format(schema: &Value) -> Value {
+  json!({
+    "type": "json_schema",
+    "json_schema": { "name": "response", "schema": schema }
+  })
+}
+
+/// Set `stream`, asking streams to report token usage in their last event
+/// (servers only send `usage` while streaming when asked for it)
+fn insert_stream_params(body: &mut Map<String, Value>, stream: bool) {
+  body.insert("stream".into(), json!(stream));
+
+  if stream {
+    body.insert("stream_options".into(), json!({ "include_usage": true }));
+  }
+}
+
+fn chat_body(request: &ChatRequest, stream: bool) -> Result<Value, String> {
+  let mut body = sampling_params(&request.options);
+
+  if let Some(schema) = &request.format {
+    body.insert("response_format".into(), response_format(schema));
+  }
+
+  let messages = request.messages
+    .iter()
+    .map(to_openai_message)
+    .collect::<Result<Vec<_>, _>>()?;
+
+  body.insert("model".into(), json!(request.model));
+  body.insert("messages".into(), Value::Array(messages));
+  insert_stream_params(&mut body, stream);
+
+  if !stream && !request.tools.is_empty() {
+    let tools: Vec<Value> = request.tools
+      .iter()
+      .map(|tool| json!({
+        "type": "function",
+        "function": {
+          "name": tool.name,
+          "description": tool.description,
+          "parameters": tool.parameters,
+        }
+      }))
+      .collect();
+
+    body.insert("tools".into(), Value::Array(tools));
+  }
+
+  Ok(Value::Object(body))
+}
+
+/// Convert a message, turning attached images into data URL content parts
+fn to_openai_message(msg: &ChatMessage) -> Result<Value, String> {
+  let mut message = json!({ "role": msg.role, "content": msg.content });
+
+  if !msg.images.is_empty() {
+    let mut parts = vec![json!({ "type": "text", "text": msg.content })];
+
+    for image in &msg.images {
+      let (data, mime_type) = image.to_base64()?;
+      parts.push(json!({
+        "type": "image_url",
+        "image_url": { "url": format!("data:{};base64,{}", mime_type, data) }
+      }));
+    }
+
+    message["content"] = Value::Array(parts);
+  }
+
+  if !msg.tool_calls.is_empty() {
+    // Arguments are sent as a JSON encoded string in the OpenAI API
+    let calls: Vec<Value> = msg.tool_calls
+      .iter()
+      .enumerate()
+      .map(|(i, call)| json!({
+        "id": call.id.clone().unwrap_or_else(|| format!("call_{}", i)),
+        "type": "function",
+        "function": { "name": call.name, "arguments": call.arguments.to_string() }
+      }))
+      .collect();
+
+    message["tool_calls"] = Value::Array(calls);
+  }
+
+  if let Some(id) = &msg.tool_call_id {
+    message["tool_call_id"] = json!(id);
+  }
+
+  Ok(message)
+}
+
+/// Read the tool calls of a chat completion message
+fn parse_tool_calls(message: &Value) -> Vec<ToolCall> {
+  let Some(calls) = message.get("tool_calls").and_then(|c| c.as_array()) else {
+    return vec![];
+  };
+
+  calls
+    .iter()
+    .filter_map(|call| {
+      let function = call.get("function")?;
+      let arguments = match function.get("arguments") {
+        Some(Value::String(raw)) => serde_json::from_str(raw).unwrap_or(Value::String(raw.clone())),
+        Some(value) => value.clone(),
+        None => Value::Null,
+      };
+
+      Some(ToolCall {
+        id: call.get("id").and_then(|id| id.as_str()).map(|id| id.to_string()),
+        name: function.get("name")?.as_str()?.to_string(),
+        arguments,
+      })
+    })
+    .collect()
+}
+
+fn completion_body(request: &GenerateRequest, stream: bool) -> Value {
+  let mut body = sampling_params(&request.options);
+
+  if let Some(schema) = &request.format {
+    body.insert("response_format".into(), response_format(schema));
+  }
+
+  body.insert("model".into(), json!(request.model));
+  body.insert("prompt".into(), json!(request.prompt));
+  insert_stream_params(&mut body, stream);
+
+  Value::Object(body)
+}
+
+/// Splits a server-sent-events body into stream chunks
+///
+/// Bytes are buffered until a full line arrives, so events and multi-byte
+/// characters split across network reads are decoded intact.
+struct SseDecoder {
+  buffer: Vec<u8>,
+  stats: Option<GenerationStats>,
+  /// JSON pointer of the text delta inside each event
+  /// (it differs between chat and completion endpoints)
+  content_pointer: &'static str,
+}
+
+impl SseDecoder {
+  fn new(content_pointer: &'static str) -> Self {
+    Self { buffer: Vec::new(), stats: None, content_pointer }
+  }
+
+  /// Feed the next read; returns the chunks of every line it completed
+  /// A `done` chunk is always last, nothing after `[DONE]` is decoded
+  fn push(&mut self, bytes: &[u8]) -> Vec<StreamChunk> {
+    self.buffer.extend_from_slice(bytes);
+    let mut chunks = Vec::new();
+
+    // Events are separated by newlines; keep any partial line in the buffer
+    while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+      let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+
+      if let Some(chunk) = self.decode_line(&String::from_utf8_lossy(&line)) {
+        let done = chunk.done;
+        chunks.push(chunk);
+
+        if done {
+          self.buffer.clear();
+          break;
+        }
+      }
+    }
+
+    chunks
+  }
+
+  /// Chunk for a stream that ended without `[DONE]`
+  fn finish(&mut self) -> StreamChunk {
+    StreamChunk { content: String::new(), done: true, stats: self.stats.take() }
+  }
+
+  fn decode_line(&mut self, line: &str) -> Option<StreamChunk> {
+    let data = line.trim().strip_prefix("data:")?.trim();
+
+    if data == "[DONE]" {
+      return Some(self.finish());
+    }
+
+    let event: Value = match serde_json::from_str(data) {
+      Ok(event) => event,
+      Err(e) => {
+        eprintln!("Failed to deserialize stream event: {}", e);
+        return None;
+      }
+    };
+
+    if let Some(usage) = event.get("usage").filter(|u| !u.is_null()) {
+      self.stats = serde_json::from_value::<Usage>(usage.clone()).ok().map(Into::into);
+    }
+
+    let content = event
+      .pointer(self.content_pointer)
+      .and_then(|c| c.as_str())
+      .unwrap_or_default();
+
+    (!content.is_empty()).then(|| StreamChunk { content: content.to_string(), done: false, stats: None })
+  }
+}
+
+/// Turn a server-sent-events response into a chunk stream
+fn sse_stream(response: reqwest::Response, content_pointer: &'static str) -> ChunkStream {
+  let mut bytes = response.bytes_stream();
+
+  let chunks = async_stream::stream! {
+    let mut decoder = SseDecoder::new(content_pointer);
+
+    while let Some(bytes) = bytes.next().await {
+      let bytes = match bytes {
+        Ok(bytes) => bytes,
+        Err(e) => {
+          yield Err(format!("OpenAI-compatible stream was interrupted: {}", e));
+          return;
+        }
+      };
+
+      for chunk in decoder.push(&bytes) {
+        let done = chunk.done;
+        yield Ok(chunk);
+
+        if done {
+          return;
+        }
+      }
+    }
+
+    // Some servers close the connection without sending [DONE]
+    yield Ok(decoder.finish());
+  };
+
+  Box::pin(chunks)
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+  async fn list_models(&self) -> Result<Vec<ProviderModel>, String> {
+    let models: ModelList = Self::send(self.get("/models"))
+      .await?
+      .json()
+      .await
+      .map_err(|e| format!("Invalid model list: {}", e))?;
+
+    Ok(models.data
+      .into_iter()
+      .map(|m| ProviderModel {
+        name: m.id,
+        // The OpenAI API does not report model sizes
+        size: 0,
+        modified_at: m.created
+          .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+          .map(|dt| dt.to_rfc3339())
+          .unwrap_or_default(),
+      })
+      .collect())
+  }
+
+  async fn chat(&self, request: ChatRequest) -> Result<ChatMessage, String> {
+    let response = Self::send_json(self.post("/chat/completions", &chat_body(&request, false)?)).await?;
+
+    let message = response
+      .pointer("/choices/0/message")
+      .ok_or_else(|| "OpenAI-compatible API returned no message".to_string())?;
+
+    // Content is null when the model only calls tools
+    let content = message
+      .get("content")
+      .and_then(|c| c.as_str())
+      .unwrap_or_default();
+
+    let mut reply = ChatMessage::assistant(content.to_string());
+    reply.tool_calls = parse_tool_calls(message);
+
+    Ok(reply)
+  }
+
+  async fn chat_stream(&self, request: ChatRequest) -> Result<ChunkStream, String> {
+    let response = self.send_stream("/chat/completions", chat_body(&request, true)?).await?;
+
+    Ok(sse_stream(response, "/choices/0/delta/content"))
+  }
+
+  async fn generate(&self, request: GenerateRequest) -> Result<String, String> {
+    let response = Self::send_json(self.post("/completions", &completion_body(&request, false))).await?;
+
+    response
+      .pointer("/choices/0/text")
+      .and_then(|t| t.as_str())
+      .map(|t| t.to_string())
+      .ok_or_else(|| "OpenAI-compatible API returned no completion".to_string())
+  }
+
+  async fn generate_stream(&self, request: GenerateRequest) -> Result<ChunkStream, String> {
+    let response = self.send_stream("/completions", completion_body(&request, true)).await?;
+
+    Ok(sse_stream(response, "/choices/0/text"))
+  }
+
+  async fn embed(&self, model: &str, input: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+    let body = json!({ "model": model, "input": input });
+
+    let mut embeddings: EmbeddingList = Self::send(self.post("/embeddings", &body))
+      .await?
+      .json()
+      .await
+      .map_err(|e| format!("Invalid embeddings response: {}", e))?;
+
+    // Entries carry their input index and are not guaranteed to be ordered
+    embeddings.data.sort_by_key(|entry| entry.index);
+
+    Ok(embeddings.data.into_iter().map(|entry| entry.embedding).collect())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CHAT_POINTER: &str = "/choices/0/delta/content";
+
+  #[test]
+  fn only_streams_ask_for_usage() {
+    let request = GenerateRequest {
+      model: "llama3".into(),
+      prompt: "Hi".into(),
+      options: GenerationOptions::default(),
+      format: None,
+    };
+
+    let streaming = completion_body(&request, true);
+    assert_eq!(streaming["stream"], true);
+    assert_eq!(streaming["stream_options"], json!({ "include_usage": true }));
+
+    let single = completion_body(&request, false);
+    assert_eq!(single["stream"], false);
+    assert!(single.get("stream_options").is_none());
+  }
+
+  fn contents(chunks: &[StreamChunk]) -> Vec<&str> {
+    chunks.iter().map(|c| c.content.as_str()).collect()
+  }
+
+  #[test]
+  fn decodes_chat_deltas_until_done() {
+    let mut decoder = SseDecoder::new(CHAT_POINTER);
+    let body = concat!(
+      "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+      "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+      "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+      "data: [DONE]\n\n",
+      "data: {\"choices\":[{\"delta\":{\"content\":\"ignored\"}}]}\n\n",
+    );
+
+    let chunks = decoder.push(body.as_bytes());
+
+    assert_eq!(contents(&chunks), ["Hel", "lo", ""]);
+    assert!(chunks[2].done);
+    assert!(!chunks[0].done && !chunks[1].done);
+  }
+
+  #[test]
+  fn keeps_partial_lines_until_they_complete() {
+    let mut decoder = SseDecoder::new("/choices/0/text");
+
+    assert!(decoder.push(b"data: {\"choices\":[{\"te").is_empty());
+    let chunks = decoder.push(b"xt\":\"Hi\"}]}\r\n");
+
+    assert_eq!(contents(&chunks), ["Hi"]);
+  }
+
+  #[test]
+  fn keeps_characters_split_across_reads() {
+    let mut decoder = SseDecoder::new(CHAT_POINTER);
+    let line = "data: {\"choices\":[{\"delta\":{\"content\":\"Grüße\"}}]}\n".as_bytes();
+    let split = line.iter().position(|&b| b == 0xC3).unwrap() + 1;
+
+    assert!(decoder.push(&line[..split]).is_empty());
+    let chunks = decoder.push(&line[split..]);
+
+    assert_eq!(contents(&chunks), ["Grüße"]);
+  }
+
+  #[test]
+  fn skips_comments_and_malformed_events() {
+    let mut decoder = SseDecoder::new(CHAT_POINTER);
+    let body = ": keep-alive\nevent: message\ndata: {not json\ndata: {\"choices\":[{\"delta\":{\"content\":\"ok\"}}]}\n";
+
+    assert_eq!(contents(&decoder.push(body.as_bytes())), ["ok"]);
+  }
+
+  #[test]
+  fn reports_usage_with_the_final_chunk() {
+    let mut decoder = SseDecoder::new(CHAT_POINTER);
+    let body = concat!(
+      "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":34}}\n",
+      "data: [DONE]\n",
+    );
+
+    let chunks = decoder.push(body.as_bytes());
+    let stats = chunks.last().and_then(|c| c.stats.as_ref()).unwrap();
+
+    assert_eq!((stats.prompt_eval_count, stats.eval_count), (12, 34));
+  }
+
+  #[test]
+  fn finishes_streams_closed_without_done() {
+    let mut decoder = SseDecoder::new(CHAT_POINTER);
+    decoder.push(b"data: {\"choices\":[],\"usage\":{\"completion_tokens\":5}}\n");
+
+    let last = decoder.finish();
+
+    assert!(last.done);
+    assert_eq!(last.stats.map(|s| s.eval_count), Some(5));
+  }
+}
@@ -7,25 +7,102 @@
 // State is automatically persisted to disk whenever it changes.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::RwLock;
 
+use crate::active_file::StorageBackend;
 use crate::files;
-use crate::ollama::{OllamaConfig, OllamaStatus};
+use crate::ollama::options::GenerationOptions;
+use crate::ollama::profiles::ServerProfile;
+use crate::ollama::requests::{ActiveLlmRequest, LlmRequestInfo};
+use crate::ollama::{OllamaConfig, ProviderStatus};
+use crate::providers::ProviderKind;
 
 const STATE_FILE_NAME: &str = "app_state.json";
 
+fn default_embedding_model() -> String {
+  "nomic-embed-text".to_string()
+}
+
+fn default_autosave_interval_secs() -> u64 {
+  3
+}
+
 // Serializable snapshot of all configs for persistence
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppConfigSnapshot {
-  pub ollama: OllamaConfig,
+  /// Named LLM server connections
+  #[serde(default)]
+  pub profiles: Vec<ServerProfile>,
+
+  /// Id of the profile used for LLM calls
+  #[serde(default)]
+  pub active_profile_id: Option<String>,
+
+  /// Legacy single connection, migrated into `profiles` on load
+  #[serde(default, skip_serializing)]
+  pub ollama: Option<OllamaConfig>,
+
+  /// Legacy provider kind of `ollama`
+  #[serde(default, skip_serializing)]
+  pub provider: Option<ProviderKind>,
+
+  /// Default generation options per model name
+  #[serde(default)]
+  pub model_options: HashMap<String, GenerationOptions>,
+
+  /// Model used for embeddings and the vector index
+  #[serde(default = "default_embedding_model")]
+  pub embedding_model: String,
+
+  /// Seconds of inactivity before the active mind map is saved (0 = off)
+  #[serde(default = "default_autosave_interval_secs")]
+  pub autosave_interval_secs: u64,
+
+  /// Where mind maps are stored
+  #[serde(default)]
+  pub storage_backend: StorageBackend,
   // Future configs added here
 }
 
+impl AppConfigSnapshot {
+  /// Move a pre-profile config into a profile and make sure one is active
+  fn migrate_profiles(&mut self) {
+    if self.profiles.is_empty() {
+      let mut profile = ServerProfile::default();
+      if let Some(config) = self.ollama.take() {
+        profile.config = config;
+      }
+      if let Some(provider) = self.provider.take() {
+        profile.provider = provider;
+      }
+      self.profiles.push(profile);
+    }
+
+    let active_exists = self.active_profile_id
+      .as_ref()
+      .is_some_and(|id| self.profiles.iter().any(|p| &p.id == id));
+
+    if !active_exists {
+      self.active_profile_id = Some(self.profiles[0].id.clone());
+    }
+  }
+}
+
 impl Default for AppConfigSnapshot {
   fn default() -> Self {
+    let profile = ServerProfile::default();
+
     Self {
-      ollama: OllamaConfig::default(),
+      active_profile_id: Some(profile.id.clone()),
+      profiles: vec![profile],
+      ollama: None,
+      provider: None,
+      model_options: HashMap::new(),
+      embedding_model: default_embedding_model(),
+      autosave_interval_secs: default_autosave_interval_secs(),
+      storage_backend: StorageBackend::default(),
     }
   }
 }
@@ -39,14 +116,32 @@ impl Default for AppConfigSnapshot {
 /// Each config field is wrapped in RwLock for thread-safe access with
 /// optimized read performance (multiple readers, single writer).
 ///
-/// Note: Only configuration is persisted to disk. Runtime status (like OllamaStatus)
+/// Note: Only configuration is persisted to disk. Runtime status (like ProviderStatus)
 /// is not persisted and resets on app restart.
 pub struct AppState {
-  /// Ollama LLM configuration (domain, port) - persisted
-  ollama_config: RwLock<OllamaConfig>,
+  /// Named LLM server connections - persisted
+  profiles: RwLock<Vec<ServerProfile>>,
+
+  /// Id of the profile used for LLM calls - persisted
+  active_profile_id: RwLock<String>,
+
+  /// Default generation options per model - persisted
+  model_options: RwLock<HashMap<String, GenerationOptions>>,
 
-  /// Ollama runtime status (availability, models) - NOT persisted
-  ollama_status: RwLock<OllamaStatus>,
+  /// Embedding model name - persisted
+  embedding_model: RwLock<String>,
+
+  /// Autosave idle delay in seconds - persisted
+  autosave_interval_secs: RwLock<u64>,
+
+  /// Mind map storage backend - persisted
+  storage_backend: RwLock<StorageBackend>,
+
+  /// Runtime status (availability, models) per profile id - NOT persisted
+  profile_statuses: RwLock<HashMap<String, ProviderStatus>>,
+
+  /// In-flight LLM requests keyed by request id - NOT persisted
+  llm_requests: RwLock<HashMap<String, ActiveLlmRequest>>,
 
   // Future configs can be added here:
   // ui_config: RwLock<UiConfig>,
@@ -56,36 +151,277 @@ pub struct AppState {
 impl AppState {
   /// Create a new AppState with default configurations
   pub fn new() -> Self {
-    Self {
-      ollama_config: RwLock::new(OllamaConfig::default()),
-      ollama_status: RwLock::new(OllamaStatus::default()),
-    }
+    Self::from_snapshot(AppConfigSnapshot::default())
   }
 
   /// Create AppState from a snapshot (used when loading from disk)
-  pub fn from_snapshot(snapshot: AppConfigSnapshot) -> Self {
+  pub fn from_snapshot(mut snapshot: AppConfigSnapshot) -> Self {
+    snapshot.migrate_profiles();
+
     Self {
-      ollama_config: RwLock::new(snapshot.ollama),
-      ollama_status: RwLock::new(OllamaStatus::default()), // Status not persisted
+      profiles: RwLock::new(snapshot.profiles),
+      active_profile_id: RwLock::new(snapshot.active_profile_id.unwrap_or_default()),
+      model_options: RwLock::new(snapshot.model_options),
+      embedding_model: RwLock::new(snapshot.embedding_model),
+      autosave_interval_secs: RwLock::new(snapshot.autosave_interval_secs),
+      storage_backend: RwLock::new(snapshot.storage_backend),
+      profile_statuses: RwLock::new(HashMap::new()), // Status not persisted
+      llm_requests: RwLock::new(HashMap::new()),
     }
   }
 
   // =========================================================================
-  // Ollama config accessors (persisted)
+  // Server profile accessors (persisted)
   // =========================================================================
 
-  /// Get a clone of the current Ollama configuration
+  /// Get all server profiles
+  pub fn get_profiles(&self) -> Vec<ServerProfile> {
+    self.profiles.read().unwrap().clone()
+  }
+
+  /// Get a profile by id
+  pub fn get_profile(&self, id: &str) -> Option<ServerProfile> {
+    self.profiles.read().unwrap()
+      .iter()
+      .find(|p| p.id == id)
+      .cloned()
+  }
+
+  /// Get the id of the active profile
+  pub fn get_active_profile_id(&self) -> String {
+    self.active_profile_id.read().unwrap().clone()
+  }
+
+  /// Get the active profile (falls back to the first one)
+  pub fn get_active_profile(&self) -> ServerProfile {
+    let active_id = self.get_active_profile_id();
+    let profiles = self.profiles.read().unwrap();
+
+    profiles.iter()
+      .find(|p| p.id == active_id)
+      .or_else(|| profiles.first())
+      .cloned()
+      .unwrap_or_default()
+  }
+
+  /// Add a profile and persist to disk
+  pub fn add_profile<R: tauri::Runtime>(
+    &self,
+    app: &tauri::AppHandle<R>,
+    profile: ServerProfile
+  ) {
+    self.profiles.write().unwrap().push(profile);
+
+    // Auto-persist after change
+    if let Err(e) = persist_app_state(app, self) {
+      eprintln!("⚠️  Failed to persist app state: {}", e);
+    }
+  }
+
+  /// Replace the profile with the same id and persist to disk
+  /// Returns false if no such profile exists
+  pub fn update_profile<R: tauri::Runtime>(
+    &self,
+    app: &tauri::AppHandle<R>,
+    profile: ServerProfile
+  ) -> bool {
+    {
+      let mut profiles = self.profiles.write().unwrap();
+      match profiles.iter_mut().find(|p| p.id == profile.id) {
+        Some(existing) => *existing = profile,
+        None => return false,
+      }
+    }
+
+    // Auto-persist after change
+    if let Err(e) = persist_app_state(app, self) {
+      eprintln!("⚠️  Failed to persist app state: {}", e);
+    }
+
+    true
+  }
+
+  /// Remove a profile and persist to disk
+  /// The last remaining profile and the active profile cannot be removed
+  pub fn remove_profile<R: tauri::Runtime>(
+    &self,
+    app: &tauri::AppHandle<R>,
+    id: &str
+  ) -> Result<(), String> {
+    if self.get_active_profile_id() == id {
+      return Err("The active profile cannot be removed".to_string());
+    }
+
+    {
+      let mut profiles = self.profiles.write().unwrap();
+      let before = profiles.len();
+      profiles.retain(|p| p.id != id);
+
+      if profiles.len() == before {
+        return Err(format!("Profile not found: {}", id));
+      }
+    }
+
+    self.profile_statuses.write().unwrap().remove(id);
+
+    // Auto-persist after change
+    if let Err(e) = persist_app_state(app, self) {
+      eprintln!("⚠️  Failed to persist app state: {}", e);
+    }
+
+    Ok(())
+  }
+
+  /// Make a profile the active one and persist to disk
+  pub fn set_active_profile<R: tauri::Runtime>(
+    &self,
+    app: &tauri::AppHandle<R>,
+    id: &str
+  ) -> Result<(), String> {
+    if self.get_profile(id).is_none() {
+      return Err(format!("Profile not found: {}", id));
+    }
+
+    *self.active_profile_id.write().unwrap() = id.to_string();
+
+    // Auto-persist after change
+    if let Err(e) = persist_app_state(app, self) {
+      eprintln!("⚠️  Failed to persist app state: {}", e);
+    }
+
+    Ok(())
+  }
+
+  /// Get a clone of the active profile's connection configuration
   pub fn get_ollama_config(&self) -> OllamaConfig {
-    self.ollama_config.read().unwrap().clone()
+    self.get_active_profile().config
   }
 
-  /// Update the Ollama configuration and persist to disk
+  /// Update the active profile's connection configuration and persist to disk
   pub fn set_ollama_config<R: tauri::Runtime>(
     &self,
     app: &tauri::AppHandle<R>,
     config: OllamaConfig
   ) {
-    *self.ollama_config.write().unwrap() = config;
+    let mut profile = self.get_active_profile();
+    profile.config = config;
+    self.update_profile(app, profile);
+  }
+
+  /// Get the kind of LLM server the active profile points at
+  pub fn get_provider(&self) -> ProviderKind {
+    self.get_active_profile().provider
+  }
+
+  /// Update the active profile's provider kind and persist to disk
+  pub fn set_provider<R: tauri::Runtime>(
+    &self,
+    app: &tauri::AppHandle<R>,
+    provider: ProviderKind
+  ) {
+    let mut profile = self.get_active_profile();
+    profile.provider = provider;
+    self.update_profile(app, profile);
+  }
+
+  // =========================================================================
+  // Model option accessors (persisted)
+  // =========================================================================
+
+  /// Get the default generation options for a model (empty if none are set)
+  pub fn get_model_options(&self, model: &str) -> GenerationOptions {
+    self.model_options.read().unwrap()
+      .get(model)
+      .cloned()
+      .unwrap_or_default()
+  }
+
+  /// Get the default generation options of every configured model
+  pub fn get_all_model_options(&self) -> HashMap<String, GenerationOptions> {
+    self.model_options.read().unwrap().clone()
+  }
+
+  /// Update the default generation options for a model and persist to disk
+  /// Empty options remove the entry
+  pub fn set_model_options<R: tauri::Runtime>(
+    &self,
+    app: &tauri::AppHandle<R>,
+    model: String,
+    options: GenerationOptions
+  ) {
+    {
+      let mut model_options = self.model_options.write().unwrap();
+      if options.is_empty() {
+        model_options.remove(&model);
+      } else {
+        model_options.insert(model, options);
+      }
+    }
+
+    // Auto-persist after change
+    if let Err(e) = persist_app_state(app, self) {
+      eprintln!("⚠️  Failed to persist app state: {}", e);
+    }
+  }
+
+  /// Get the model used for embeddings
+  pub fn get_embedding_model(&self) -> String {
+    self.embedding_model.read().unwrap().clone()
+  }
+
+  /// Update the embedding model and persist to disk
+  pub fn set_embedding_model<R: tauri::Runtime>(
+    &self,
+    app: &tauri::AppHandle<R>,
+    model: String
+  ) {
+    *self.embedding_model.write().unwrap() = model;
+
+    // Auto-persist after change
+    if let Err(e) = persist_app_state(app, self) {
+      eprintln!("⚠️  Failed to persist app state: {}", e);
+    }
+  }
+
+  // =========================================================================
+  // Autosave accessors (persisted)
+  // =========================================================================
+
+  /// Get the autosave idle delay in seconds (0 = disabled)
+  pub fn get_autosave_interval_secs(&self) -> u64 {
+    *self.autosave_interval_secs.read().unwrap()
+  }
+
+  /// Update the autosave idle delay and persist to disk
+  pub fn set_autosave_interval_secs<R: tauri::Runtime>(
+    &self,
+    app: &tauri::AppHandle<R>,
+    seconds: u64
+  ) {
+    *self.autosave_interval_secs.write().unwrap() = seconds;
+
+    // Auto-persist after change
+    if let Err(e) = persist_app_state(app, self) {
+      eprintln!("⚠️  Failed to persist app state: {}", e);
+    }
+  }
+
+  // =========================================================================
+  // Storage accessors (persisted)
+  // =========================================================================
+
+  /// Get where mind maps are stored
+  pub fn get_storage_backend(&self) -> StorageBackend {
+    *self.storage_backend.read().unwrap()
+  }
+
+  /// Switch the mind map storage backend and persist to disk
+  pub fn set_storage_backend<R: tauri::Runtime>(
+    &self,
+    app: &tauri::AppHandle<R>,
+    backend: StorageBackend
+  ) {
+    *self.storage_backend.write().unwrap() = backend;
 
     // Auto-persist after change
     if let Err(e) = persist_app_state(app, self) {
@@ -94,17 +430,78 @@ impl AppState {
   }
 
   // =========================================================================
-  // Ollama status accessors (runtime only, not persisted)
+  // Provider status accessors (runtime only, not persisted)
   // =========================================================================
 
-  /// Get a clone of the current Ollama status
-  pub fn get_ollama_status(&self) -> OllamaStatus {
-    self.ollama_status.read().unwrap().clone()
+  /// Get a clone of the active profile's status
+  pub fn get_provider_status(&self) -> ProviderStatus {
+    let profile = self.get_active_profile();
+
+    self.profile_statuses.read().unwrap()
+      .get(&profile.id)
+      .cloned()
+      .unwrap_or_else(|| ProviderStatus::for_profile(&profile))
+  }
+
+  /// Get the status of every profile keyed by profile id
+  pub fn get_profile_statuses(&self) -> HashMap<String, ProviderStatus> {
+    self.profile_statuses.read().unwrap().clone()
   }
 
-  /// Update the Ollama status (does NOT persist - runtime only)
-  pub fn set_ollama_status(&self, status: OllamaStatus) {
-    *self.ollama_status.write().unwrap() = status;
+  /// Update a profile's status (does NOT persist - runtime only)
+  pub fn set_profile_status(&self, status: ProviderStatus) {
+    self.profile_statuses.write().unwrap()
+      .insert(status.profile_id.clone(), status);
+  }
+
+  // =========================================================================
+  // LLM request registry (runtime only, not persisted)
+  // =========================================================================
+
+  /// Register a running LLM request so it can be listed and cancelled
+  /// Fails without registering if a request with the same id is already running
+  pub(crate) fn try_register_llm_request(&self, request: ActiveLlmRequest) -> Result<(), String> {
+    let mut requests = self.llm_requests.write().unwrap();
+
+    if requests.contains_key(&request.info.request_id) {
+      return Err(format!("A request with id {} is already running", request.info.request_id));
+    }
+
+    requests.insert(request.info.request_id.clone(), request);
+    Ok(())
+  }
+
+  /// Remove a finished (or cancelled) LLM request from the registry
+  /// Only removes the entry if it still belongs to the task with `task_id`
+  pub(crate) fn remove_llm_request(&self, request_id: &str, task_id: tokio::task::Id) {
+    let mut requests = self.llm_requests.write().unwrap();
+
+    if requests.get(request_id).is_some_and(|request| request.abort_handle.id() == task_id) {
+      requests.remove(request_id);
+    }
+  }
+
+  /// Get the public info of all running LLM requests, oldest first
+  pub fn list_llm_requests(&self) -> Vec<LlmRequestInfo> {
+    let mut requests: Vec<LlmRequestInfo> = self.llm_requests.read().unwrap()
+      .values()
+      .map(|request| request.info.clone())
+      .collect();
+
+    requests.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    requests
+  }
+
+  /// Abort a running LLM request
+  /// Returns false if no request with that id is running
+  pub fn cancel_llm_request(&self, request_id: &str) -> bool {
+    match self.llm_requests.read().unwrap().get(request_id) {
+      Some(request) => {
+        request.abort_handle.abort();
+        true
+      }
+      None => false,
+    }
   }
 
   // =========================================================================
@@ -114,7 +511,14 @@ impl AppState {
   /// Create a snapshot of all current configurations (excludes runtime status)
   pub fn snapshot(&self) -> AppConfigSnapshot {
     AppConfigSnapshot {
-      ollama: self.get_ollama_config(),
+      profiles: self.get_profiles(),
+      active_profile_id: Some(self.get_active_profile_id()),
+      ollama: None,
+      provider: None,
+      model_options: self.get_all_model_options(),
+      embedding_model: self.get_embedding_model(),
+      autosave_interval_secs: self.get_autosave_interval_secs(),
+      storage_backend: self.get_storage_backend(),
     }
   }
 }
@@ -181,7 +585,7 @@ pub fn persist_app_state<R: tauri::Runtime>(
 
   let state_file_path = config_dir.join(STATE_FILE_NAME);
 
-  std::fs::write(&state_file_path, json_string)
+  files::write_atomic(&state_file_path, json_string)
     .map_err(|e| format!("Failed to write state file: {}", e))?;
 
   println!("💾 App state persisted to: {:?}", state_file_path);
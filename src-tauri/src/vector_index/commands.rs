@@ -0,0 +1,50 @@
+// Tauri command handlers for the vector index
+use tauri::{AppHandle, Manager, State};
+
+use super::{rebuild_index, search_text, RelatedNode, VectorIndexManager};
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: usize = 10;
+
+/// Find the nodes across all indexed mind maps that are most related to a query
+#[tauri::command]
+pub async fn find_related_nodes(
+  app: AppHandle,
+  query: String,
+  limit: Option<usize>
+) -> Result<Vec<RelatedNode>, String> {
+  search_text(&app, &query, limit.unwrap_or(DEFAULT_LIMIT)).await
+}
+
+/// Find the nodes across all indexed mind maps that are most related to an indexed node
+#[tauri::command]
+pub fn find_nodes_related_to(
+  app: AppHandle,
+  index: State<'_, VectorIndexManager>,
+  file_name: String,
+  node_id: String,
+  limit: Option<usize>
+) -> Result<Vec<RelatedNode>, String> {
+  let model = app.state::<AppState>().get_embedding_model();
+
+  let embedding = index.node_embedding(&file_name, &node_id)
+    .ok_or_else(|| format!("Node {} of {} is not indexed yet", node_id, file_name))?;
+
+  Ok(index.search(
+    &model,
+    &embedding,
+    limit.unwrap_or(DEFAULT_LIMIT),
+    Some((&file_name, &node_id))
+  ))
+}
+
+/// Re-embed every saved mind map
+/// Returns the number of mind maps indexed
+#[tauri::command]
+pub async fn rebuild_vector_index(app: AppHandle) -> Result<usize, String> {
+  let indexed = rebuild_index(&app).await?;
+
+  println!("🧭 Vector index rebuilt ({} mind maps)", indexed);
+
+  Ok(indexed)
+}
@@ -0,0 +1,479 @@
+// Vector index over mind map nodes
+//
+// Node content from every saved mind map is embedded with the configured
+// embedding model and persisted as JSON next to the app state. The index is
+// updated incrementally whenever a mind map is written to disk: only nodes
+// whose content changed since the last run are sent to Ollama again.
+//
+// This module is organized into:
+// - mod.rs: Index types, persistence and incremental updates
+// - commands: Tauri command handlers (search, rebuild)
+
+pub mod commands;
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use tauri::{AppHandle, Manager};
+
+use crate::active_file::MindMap;
+use crate::files;
+use crate::ollama::embeddings::embed_texts;
+use crate::state::AppState;
+
+const INDEX_FILE_NAME: &str = "vector_index.json";
+
+/// Maximum number of characters of node content kept as a preview
+const PREVIEW_LENGTH: usize = 160;
+
+/// An embedded node
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedNode {
+  pub node_id: String,
+  pub node_type: String,
+  pub preview: String,
+  pub content_hash: u64,
+  pub embedding: Vec<f32>,
+}
+
+/// All embedded nodes of one mind map file
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedMindMap {
+  pub name: String,
+  pub nodes: Vec<IndexedNode>,
+}
+
+/// Persisted index - embeddings are only comparable within one model
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorIndex {
+  pub model: String,
+  /// Indexed mind maps keyed by their file path
+  pub mind_maps: HashMap<String, IndexedMindMap>,
+}
+
+/// A search hit returned to the frontend
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedNode {
+  pub file_name: String,
+  pub mind_map_name: String,
+  pub node_id: String,
+  pub node_type: String,
+  pub preview: String,
+  pub score: f32,
+}
+
+/// Text extracted from a node, ready to be embedded
+#[derive(Debug, Clone)]
+struct NodeDocument {
+  node_id: String,
+  node_type: String,
+  text: String,
+}
+
+/// Managed state holding the in-memory copy of the index
+pub struct VectorIndexManager {
+  index: RwLock<VectorIndex>,
+
+  // Serializes index updates so two saves of the same map can't interleave
+  update_lock: tokio::sync::Mutex<()>,
+
+  // Taken by every scheduled change, in the order the changes were made
+  next_generation: AtomicU64,
+
+  // Generation of the last change applied to each mind map
+  applied_generations: Mutex<HashMap<String, u64>>,
+}
+
+impl VectorIndexManager {
+  fn new(index: VectorIndex) -> Self {
+    Self {
+      index: RwLock::new(index),
+      update_lock: tokio::sync::Mutex::new(()),
+      next_generation: AtomicU64::new(0),
+      applied_generations: Mutex::new(HashMap::new()),
+    }
+  }
+
+  fn next_generation(&self) -> u64 {
+    self.next_generation.fetch_add(1, Ordering::SeqCst)
+  }
+
+  /// Record that a change is being applied to a mind map's entry
+  /// Returns false for a change made before the last one applied, which is skipped
+  fn claim_generation(&self, file_name: &str, generation: u64) -> bool {
+    let mut applied = self.applied_generations.lock().unwrap();
+
+    if applied.get(file_name).is_some_and(|&latest| latest > generation) {
+      return false;
+    }
+
+    applied.insert(file_name.to_string(), generation);
+    true
+  }
+
+  /// Find the indexed nodes closest to the given embedding
+  fn search(
+    &self,
+    model: &str,
+    query: &[f32],
+    limit: usize,
+    exclude: Option<(&str, &str)>
+  ) -> Vec<RelatedNode> {
+    let index = self.index.read().unwrap();
+
+    // Embeddings from another model live in a different vector space
+    if index.model != model {
+      return vec![];
+    }
+
+    let mut hits: Vec<RelatedNode> = index.mind_maps
+      .iter()
+      .flat_map(|(file_name, map)| {
+        map.nodes.iter().filter_map(move |node| {
+          if exclude == Some((file_name.as_str(), node.node_id.as_str())) {
+            return None;
+          }
+
+          Some(RelatedNode {
+            file_name: file_name.clone(),
+            mind_map_name: map.name.clone(),
+            node_id: node.node_id.clone(),
+            node_type: node.node_type.clone(),
+            preview: node.preview.clone(),
+            score: cosine_similarity(query, &node.embedding),
+          })
+        })
+      })
+      .collect();
+
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    hits
+  }
+
+  /// Get the stored embedding of a node
+  fn node_embedding(&self, file_name: &str, node_id: &str) -> Option<Vec<f32>> {
+    self.index.read().unwrap()
+      .mind_maps
+      .get(file_name)?
+      .nodes
+      .iter()
+      .find(|node| node.node_id == node_id)
+      .map(|node| node.embedding.clone())
+  }
+}
+
+// ============================================================================
+// Initialization & persistence
+// ============================================================================
+
+/// Load the vector index from disk (empty index if missing or unreadable)
+pub fn initialize_vector_index<R: tauri::Runtime>(app: &tauri::App<R>) -> VectorIndexManager {
+  let index = match load_vector_index(app.handle()) {
+    Ok(index) => {
+      println!("✅ Loaded vector index ({} mind maps)", index.mind_maps.len());
+      index
+    }
+    Err(e) => {
+      println!("📝 No vector index loaded ({}), starting empty", e);
+      VectorIndex::default()
+    }
+  };
+
+  VectorIndexManager::new(index)
+}
+
+fn load_vector_index<R: tauri::Runtime>(app: &AppHandle<R>) -> Result<VectorIndex, String> {
+  let index_path = files::build_config_path(app)?.join(INDEX_FILE_NAME);
+
+  let json_string = files::load_text_file(&index_path)?;
+
+  serde_json::from_str(&json_string)
+    .map_err(|e| format!("Failed to deserialize vector index: {}", e))
+}
+
+fn persist_vector_index<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  index: &VectorIndex
+) -> Result<(), String> {
+  let json_string = serde_json::to_string(index)
+    .map_err(|e| format!("Failed to serialize vector index: {}", e))?;
+
+  let config_dir = files::build_config_path(app)?;
+
+  std::fs::create_dir_all(&config_dir)
+    .map_err(|e| format!("Failed to create config directory: {}", e))?;
+
+  files::write_atomic(&config_dir.join(INDEX_FILE_NAME), json_string)
+    .map_err(|e| format!("Failed to write vector index: {}", e))
+}
+
+// ============================================================================
+// Indexing
+// ============================================================================
+
+/// Re-index a mind map in the background after it was written to disk
+/// Failures (e.g. Ollama offline) are logged and retried on the next save
+pub fn schedule_index_update<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  file_name: &str,
+  mind_map: &MindMap
+) {
+  let app = app.clone();
+  let file_name = file_name.to_string();
+  let name = mind_map.name.clone();
+  let documents = extract_node_documents(mind_map);
+  let generation = app.state::<VectorIndexManager>().next_generation();
+
+  tauri::async_runtime::spawn(async move {
+    match index_mind_map(&app, &file_name, name, documents, generation).await {
+      Ok(embedded) if embedded > 0 => {
+        println!("🧭 Vector index updated for {} ({} nodes embedded)", file_name, embedded);
+      }
+      Ok(_) => {}
+      Err(e) => eprintln!("⚠️  Failed to update vector index for {}: {}", file_name, e),
+    }
+  });
+}
+
+/// Drop a mind map from the index in the background (after it was moved or deleted)
+pub fn schedule_index_removal<R: tauri::Runtime>(app: &AppHandle<R>, file_name: &str) {
+  let app = app.clone();
+  let file_name = file_name.to_string();
+  let generation = app.state::<VectorIndexManager>().next_generation();
+
+  tauri::async_runtime::spawn(async move {
+    let manager = app.state::<VectorIndexManager>();
+    let _guard = manager.update_lock.lock().await;
+
+    if !manager.claim_generation(&file_name, generation) {
+      return;
+    }
+
+    let index = {
+      let mut index = manager.index.write().unwrap();
+      if index.mind_maps.remove(&file_name).is_none() {
+        return;
+      }
+      index.clone()
+    };
+
+    if let Err(e) = persist_vector_index(&app, &index) {
+      eprintln!("⚠️  Failed to update vector index for {}: {}", file_name, e);
+    }
+  });
+}
+
+/// Embed the changed nodes of one mind map and replace its index entry
+/// Skipped when a later change of the map was applied already (`generation`)
+/// Returns the number of nodes that had to be (re-)embedded
+async fn index_mind_map<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  file_name: &str,
+  name: String,
+  documents: Vec<NodeDocument>,
+  generation: u64
+) -> Result<usize, String> {
+  let manager = app.state::<VectorIndexManager>();
+  let _guard = manager.update_lock.lock().await;
+
+  if !manager.claim_generation(file_name, generation) {
+    return Ok(0);
+  }
+
+  let model = app.state::<AppState>().get_embedding_model();
+
+  // Reuse embeddings of nodes whose content did not change
+  let previous: HashMap<String, IndexedNode> = {
+    let index = manager.index.read().unwrap();
+
+    if index.model == model {
+      index.mind_maps
+        .get(file_name)
+        .map(|map| map.nodes.iter().map(|n| (n.node_id.clone(), n.clone())).collect())
+        .unwrap_or_default()
+    } else {
+      HashMap::new()
+    }
+  };
+
+  let mut nodes = Vec::with_capacity(documents.len());
+  let mut pending = Vec::new();
+
+  for document in documents {
+    let content_hash = hash_text(&document.text);
+
+    match previous.get(&document.node_id) {
+      Some(existing) if existing.content_hash == content_hash => {
+        nodes.push(IndexedNode {
+          node_type: document.node_type,
+          ..existing.clone()
+        });
+      }
+      _ => pending.push((document, content_hash)),
+    }
+  }
+
+  let texts: Vec<String> = pending.iter().map(|(doc, _)| doc.text.clone()).collect();
+  let embeddings = embed_texts(app, &model, texts).await?;
+  let embedded = embeddings.len();
+
+  for ((document, content_hash), embedding) in pending.into_iter().zip(embeddings) {
+    nodes.push(IndexedNode {
+      node_id: document.node_id,
+      node_type: document.node_type,
+      preview: document.text.chars().take(PREVIEW_LENGTH).collect(),
+      content_hash,
+      embedding,
+    });
+  }
+
+  let index = {
+    let mut index = manager.index.write().unwrap();
+
+    // Switching models invalidates every stored vector
+    if index.model != model {
+      index.model = model;
+      index.mind_maps.clear();
+    }
+
+    index.mind_maps.insert(file_name.to_string(), IndexedMindMap { name, nodes });
+    index.clone()
+  };
+
+  persist_vector_index(app, &index)?;
+
+  Ok(embedded)
+}
+
+/// Embed a text query and find the closest indexed nodes
+pub(crate) async fn search_text<R: tauri::Runtime>(
+  app: &AppHandle<R>,
+  query: &str,
+  limit: usize
+) -> Result<Vec<RelatedNode>, String> {
+  let model = app.state::<AppState>().get_embedding_model();
+
+  let embedding = embed_texts(app, &model, vec![query.to_string()])
+    .await?
+    .pop()
+    .ok_or_else(|| "Provider returned no embedding".to_string())?;
+
+  Ok(app.state::<VectorIndexManager>().search(&model, &embedding, limit, None))
+}
+
+/// Re-index every mind map in the selected storage and drop the ones that no longer exist
+/// Returns the number of mind maps that were indexed
+pub(crate) async fn rebuild_index<R: tauri::Runtime>(app: &AppHandle<R>) -> Result<usize, String> {
+  use crate::active_file::{load_mind_map_from_disk, storage};
+
+  let summaries = storage(app)?.list()?;
+
+  prune_index(app, summaries.iter().map(|summary| summary.file_name.as_str()).collect()).await?;
+
+  let file_names: Vec<String> = summaries
+    .into_iter()
+    .filter(|summary| summary.error.is_none())
+    .map(|summary| summary.file_name)
+    .collect();
+
+  let manager = app.state::<VectorIndexManager>();
+  let mut indexed = 0;
+
+  for file_name in file_names {
+    let generation = manager.next_generation();
+
+    let mind_map = match load_mind_map_from_disk(app, &file_name) {
+      Ok(map) => map,
+      Err(e) => {
+        eprintln!("⚠️  Skipping {} while indexing: {}", file_name, e);
+        continue;
+      }
+    };
+
+    let documents = extract_node_documents(&mind_map);
+    index_mind_map(app, &file_name, mind_map.name.clone(), documents, generation).await?;
+    indexed += 1;
+  }
+
+  Ok(indexed)
+}
+
+/// Remove the entries of mind maps that aren't in `existing` (deleted outside the app)
+async fn prune_index<R: tauri::Runtime>(app: &AppHandle<R>, existing: HashSet<&str>) -> Result<(), String> {
+  let manager = app.state::<VectorIndexManager>();
+  let _guard = manager.update_lock.lock().await;
+
+  let index = {
+    let mut index = manager.index.write().unwrap();
+    let before = index.mind_maps.len();
+
+    index.mind_maps.retain(|file_name, _| existing.contains(file_name.as_str()));
+
+    if index.mind_maps.len() == before {
+      return Ok(());
+    }
+
+    println!("🧭 Removed {} deleted mind map(s) from the vector index", before - index.mind_maps.len());
+    index.clone()
+  };
+
+  persist_vector_index(app, &index)
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Pull the embeddable text out of each node
+///
+/// Every node contributes its `content`, chat nodes followed by the AI
+/// response. Nodes without text are skipped.
+fn extract_node_documents(mind_map: &MindMap) -> Vec<NodeDocument> {
+  mind_map.nodes
+    .iter()
+    .filter_map(|node| {
+      let node_id = node.id()?.to_string();
+      let node_type = node.node_type().to_string();
+      let text = node.text_parts().join("\n\n");
+
+      if text.is_empty() {
+        return None;
+      }
+
+      Some(NodeDocument { node_id, node_type, text })
+    })
+    .collect()
+}
+
+/// Hash node text to detect changes between saves
+/// DefaultHasher is only used for change detection; a different hash after a
+/// toolchain upgrade just causes one extra re-embed.
+fn hash_text(text: &str) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  text.hash(&mut hasher);
+  hasher.finish()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+  if a.len() != b.len() || a.is_empty() {
+    return 0.0;
+  }
+
+  let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+  let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+  let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+  if norm_a == 0.0 || norm_b == 0.0 {
+    return 0.0;
+  }
+
+  dot / (norm_a * norm_b)
+}
@@ -9,7 +9,7 @@ import { createChatHistory } from "@/lib/chat-parsing";
 import { EnterHandler } from "@/lib/events/keyboard";
 import { useTauriListener } from "@/lib/hooks/useTauriListener";
 import { NodeDefinitionInput } from "@/lib/models/base-node.data";
-import { getOllamaStatus, OllamaStatus } from "@/lib/ollama.service";
+import { getProviderStatus, ProviderStatus } from "@/lib/ollama.service";
 import { BaseProps } from "@/lib/utility-types";
 import { invoke } from "@tauri-apps/api/core";
 import { Node, useReactFlow } from "@xyflow/react";
@@ -162,15 +162,15 @@ function Header(props: LLMNodeProps) {
 }
 
 function Menu(props: Node<ChatNodeData, string>) {
-  const [models, setModels] = useState<OllamaStatus["models"]>([]);
+  const [models, setModels] = useState<ProviderStatus["models"]>([]);
   const { updateNodeData } = useReactFlow();
 
   // Listen for real-time Ollama status updates
-  const ollamaStatus = useTauriListener<OllamaStatus | null>("ollama-status-changed", null);
+  const ollamaStatus = useTauriListener<ProviderStatus | null>("ollama-status-changed", null);
 
   // Fetch initial Ollama status on mount
   useEffect(() => {
-    getOllamaStatus().then((status) => setModels(status.models));
+    getProviderStatus().then((status) => setModels(status.models));
   }, []);
 
   // Update models when status changes via event
@@ -1,7 +1,9 @@
 import { invoke } from "@tauri-apps/api/core";
+import { message } from "@tauri-apps/plugin-dialog";
 import { useCallback, useEffect, useState } from "preact/hooks";
 import { toast } from "sonner";
-import { useTauriListener } from "./useTauriListener";
+import { ConflictResolution, FlushError, resolveFlushConflict } from "../mindMap.service";
+import { useTauriEvent, useTauriListener } from "./useTauriListener";
 
 export enum SavingStates {
   SAVED = 'saved',
@@ -14,6 +16,31 @@ export interface SaveState {
   hasUnsavedChanges: boolean;
 }
 
+function isFlushError(error: unknown): error is FlushError {
+  return typeof error === 'object' && error !== null && 'kind' in error;
+}
+
+/**
+ * Ask the user how to resolve a file that changed on disk since it was loaded
+ * Dismissing the dialog saves a copy, the only choice that loses nothing
+ */
+async function askConflictResolution(error: FlushError): Promise<ConflictResolution> {
+  const choice = await message(
+    `${error.message}\n\nOverwrite it with your changes, reload it from disk (discarding your changes), or save your changes as a copy?`,
+    {
+      title: 'Mind Map Changed on Disk',
+      kind: 'warning',
+      buttons: { yes: 'Overwrite', no: 'Reload', cancel: 'Save as Copy' }
+    }
+  );
+
+  switch (choice) {
+    case 'Overwrite': return 'overwrite';
+    case 'Reload': return 'reload';
+    default: return 'saveAsCopy';
+  }
+}
+
 /**
  * Hook to manage save state for the mind map
  * Provides save functionality, auto-save, and keyboard shortcuts
@@ -42,16 +69,33 @@ export function useSaveState(
     await invoke('update_nodes', { nodes: getNodes() });
     await invoke('update_edges', { edges: getEdges() });
 
-    await invoke('flush_mind_map');
+    try {
+      await invoke('flush_mind_map');
+    } catch (error) {
+      if (!isFlushError(error) || error.kind !== 'conflict') {
+        toast.error(`Failed to save mind map: ${isFlushError(error) ? error.message : error}`);
+        markUnsaved();
+        return;
+      }
+
+      const resolution = await askConflictResolution(error);
+      await resolveFlushConflict(resolution);
+    }
 
     toast.success('Mind Map Saved Successfully', { duration: 2000 })
     markSaved();
-  }, [savingState, stateEvents, markSaved]);
+  }, [savingState, stateEvents, markSaved, markUnsaved]);
 
   useEffect(() => {
     if (!savingState.isSaving) markSaved()
   }, [savingState])
 
+  // Autosave stops writing until the conflict it ran into is resolved
+  useTauriEvent<FlushError>('aiMindMap://mindMap/autosave/conflict', async (error) => {
+    const resolution = await askConflictResolution(error);
+    await resolveFlushConflict(resolution);
+  });
+
   return {
     markUnsaved,
     markSaved,
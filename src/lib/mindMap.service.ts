@@ -1,8 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { Edge, Node, ReactFlowJsonObject } from "@xyflow/react";
+import { Edge, Node, ReactFlowJsonObject, Viewport } from "@xyflow/react";
 import nodeRegistry from "./node-registry";
-import { MindMap, PersistentMindMap } from "./types/mind-map";
+import { MindMap, MindMapUiState, PersistentMindMap } from "./types/mind-map";
 import { Nullable } from "./utility-types";
 
 
@@ -40,6 +40,34 @@ export async function onMindMapUpdate(callback: (mindMap: MindMap) => void) {
   ));
 }
 
+/** Sent when the open mind map changed on disk while it has unsaved edits */
+export interface ExternalChangeConflict {
+  fileName: string;
+  local: MindMap;
+  disk: MindMap;
+}
+
+export async function onMindMapConflict(callback: (conflict: ExternalChangeConflict) => void) {
+  return await listen<{ fileName: string, local: PersistentMindMap, disk: PersistentMindMap }>('aiMindMap://mindMap/conflict', (event) => callback({
+    fileName: event.payload.fileName,
+    local: toMindMap(event.payload.local),
+    disk: toMindMap(event.payload.disk),
+  }));
+}
+
+/** Sent after a mind map file saved in an older format was upgraded */
+export interface FormatMigrationReport {
+  fileName: string;
+  fromVersion: number;
+  toVersion: number;
+  applied: string[];
+  backupPath: string;
+}
+
+export async function onMindMapMigrated(callback: (report: FormatMigrationReport) => void) {
+  return await listen<FormatMigrationReport>('aiMindMap://mindMap/migrated', (event) => callback(event.payload));
+}
+
 export async function loadMindMap() {
   return await invoke<PersistentMindMap>("get_mind_map").then(toMindMap);
 }
@@ -52,6 +80,102 @@ function toMindMap(persistence: PersistentMindMap): MindMap {
   };
 }
 
+/** Store the viewport of the open mind map without marking it unsaved */
+export async function updateViewport(viewport: Viewport) {
+  return await invoke<void>("update_viewport", { viewport });
+}
+
+/** Store the viewport, layout direction and collapsed branches without marking the map unsaved */
+export async function updateUiState(uiState: MindMapUiState) {
+  return await invoke<void>("update_ui_state", { uiState });
+}
+
+export type DiagnosticKind =
+  | 'missingNodeId'
+  | 'duplicateNodeId'
+  | 'danglingEdge'
+  | 'selfLoop'
+  | 'duplicateEdge'
+  | 'cycle'
+  | 'multipleParents';
+
+/** A structural problem in a mind map */
+export interface MindMapDiagnostic {
+  kind: DiagnosticKind;
+  message: string;
+  nodeIds: string[];
+  edgeIds: string[];
+  /** Whether `repairMindMap` fixes this problem */
+  repairable: boolean;
+}
+
+export interface ValidationReport {
+  fileName: string;
+  /** Problems still present in the mind map */
+  diagnostics: MindMapDiagnostic[];
+  /** Problems that were fixed */
+  repaired: MindMapDiagnostic[];
+}
+
+/** Check the open mind map, or a stored one as it is on disk, for structural problems */
+export async function validateMindMap(fileName?: string) {
+  return await invoke<ValidationReport>("validate_mind_map", { fileName });
+}
+
+/** Fix the problems of the open mind map that can be fixed safely */
+export async function repairMindMap() {
+  return await invoke<ValidationReport>("repair_mind_map");
+}
+
+/** Sent when a loaded mind map had structural problems (fixable ones are repaired while loading) */
+export async function onMindMapDiagnostics(callback: (report: ValidationReport) => void) {
+  return await listen<ValidationReport>('aiMindMap://mindMap/diagnostics', (event) => callback(event.payload));
+}
+
+/** Why a mind map could not be loaded */
+export interface MindMapLoadError {
+  fileName: string;
+  message: string;
+}
+
+/** A recovered mind map waiting for review */
+export interface RecoveryProposal {
+  fileName: string;
+  method: 'tolerantParse' | 'llm';
+  mindMap: MindMap;
+  /** What was changed to make the file usable */
+  notes: string[];
+  /** Structural problems that could not be repaired */
+  diagnostics: MindMapDiagnostic[];
+}
+
+export async function getLoadError() {
+  return await invoke<MindMapLoadError | null>("get_load_error");
+}
+
+export async function clearLoadError() {
+  return await invoke<void>("clear_load_error");
+}
+
+/**
+ * Recover a mind map file that can no longer be loaded (defaults to the file of the last load error).
+ * Tolerant parsing is tried first; `model` is used to reconstruct the map when that fails.
+ */
+export async function recoverMindMap(options: { fileName?: string, model?: string, requestId?: string } = {}) {
+  const proposal = await invoke<Omit<RecoveryProposal, 'mindMap'> & { mindMap: PersistentMindMap }>("recover_mind_map", options);
+
+  return { ...proposal, mindMap: toMindMap(proposal.mindMap) };
+}
+
+/** Replace the corrupted file with the reviewed recovery and open it, returns the backup's path */
+export async function applyMindMapRecovery(fileName: string) {
+  return await invoke<string>("apply_mind_map_recovery", { fileName });
+}
+
+export async function discardMindMapRecovery() {
+  return await invoke<void>("discard_mind_map_recovery");
+}
+
 interface UpdateMindMapHelpers {
   clone: (mindMap: Nullable<MindMap>, flowElements: ReactFlowJsonObject<Node, Edge>) => MindMap
   update: (mindMap: MindMap) => void;
@@ -84,4 +208,111 @@ export function onUpdateMindMap(setupCallback: (helpers: UpdateMindMapHelpers) =
     })
   }
   
-}
\ No newline at end of file
+}
+/** Overview of a mind map saved in the data directory */
+export interface MindMapSummary {
+  fileName: string;
+  name: string;
+  description: string;
+  createdAt: string;
+  updatedAt: string;
+  nodeCount: number;
+  edgeCount: number;
+  fileSize: number;
+  modifiedAt?: string;
+  /** Set when the file could not be read or parsed */
+  error?: string;
+}
+
+/** List the saved mind maps, most recently modified first */
+export function listMindMaps() {
+  return invoke<MindMapSummary[]>("list_mind_maps");
+}
+
+/** Summary of a stored snapshot of a mind map */
+export interface MindMapVersion {
+  versionId: string;
+  createdAt: string;
+  name: string;
+  nodeCount: number;
+  edgeCount: number;
+}
+
+/** List saved versions (newest first), defaults to the active mind map */
+export function listMindMapVersions(fileName?: string) {
+  return invoke<MindMapVersion[]>("list_mind_map_versions", { fileName });
+}
+
+export async function previewMindMapVersion(versionId: string, fileName?: string) {
+  return await invoke<PersistentMindMap>("preview_mind_map_version", { fileName, versionId }).then(toMindMap);
+}
+
+/** Make a saved version the active mind map (it stays unsaved until flushed) */
+export function restoreMindMapVersion(versionId: string, fileName?: string) {
+  return invoke<void>("restore_mind_map_version", { fileName, versionId });
+}
+
+/** Seconds of inactivity before the backend saves the active mind map (0 = disabled) */
+export function getAutosaveInterval() {
+  return invoke<number>("get_autosave_interval");
+}
+
+export function setAutosaveInterval(seconds: number) {
+  return invoke<void>("set_autosave_interval", { seconds });
+}
+
+/** Save the active mind map to a new file (shows a dialog without a path), returns the new file name */
+export function saveMindMapAs(path?: string) {
+  return invoke<string>("save_mind_map_as", { path });
+}
+
+/** Rename a mind map (defaults to the active one) to a display name or absolute path, returns the new file name */
+export function renameMindMap(newName: string, fileName?: string) {
+  return invoke<string>("rename_mind_map", { fileName, newName });
+}
+
+/** Copy a mind map (defaults to the active one), returns the copy's file name */
+export function duplicateMindMap(fileName?: string) {
+  return invoke<string>("duplicate_mind_map", { fileName });
+}
+
+/** Move a mind map to the trash folder */
+export function deleteMindMap(fileName: string) {
+  return invoke<void>("delete_mind_map", { fileName });
+}
+
+/** Error returned by `flush_mind_map` */
+export type FlushError =
+  | { kind: "conflict"; fileName: string; message: string }
+  | { kind: "failed"; message: string };
+
+export type ConflictResolution = "overwrite" | "reload" | "saveAsCopy";
+
+/** Resolve a flush conflict, returns the file name of the active mind map afterwards */
+export function resolveFlushConflict(resolution: ConflictResolution) {
+  return invoke<string>("resolve_flush_conflict", { resolution });
+}
+
+/** Where mind maps are stored: JSON files in the data directory or the SQLite database */
+export type StorageBackend = "json" | "sqlite";
+
+export interface StorageMigrationReport {
+  migrated: string[];
+  /** Already in the database and not overwritten */
+  skipped: string[];
+  failed: { fileName: string; error: string }[];
+}
+
+export function getStorageBackend() {
+  return invoke<StorageBackend>("get_storage_backend");
+}
+
+/** Switch the storage backend (existing mind maps are not moved) */
+export function setStorageBackend(backend: StorageBackend) {
+  return invoke<void>("set_storage_backend", { backend });
+}
+
+/** Copy the JSON mind maps into the SQLite database, the files are kept */
+export function migrateMindMapsToSqlite(overwrite = false) {
+  return invoke<StorageMigrationReport>("migrate_mind_maps_to_sqlite", { overwrite });
+}
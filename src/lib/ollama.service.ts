@@ -1,24 +1,46 @@
 import { invoke } from "@tauri-apps/api/core";
-import { ChatMessage } from "./types/conversation";
+import { ChatMessage, ToolCall } from "./types/conversation";
+
+/** Kind of server the connection points at */
+export type ProviderKind = "ollama" | "openAiCompatible";
 
 export interface OllamaConfig {
   domain: string;
   port: number;
+  /** Bearer token for OpenAI-compatible servers that require one */
+  apiKey?: string;
 }
 
-export interface OllamaModel {
+export interface ProviderModel {
   name: string;
   size: number;
   modified_at: string;
 }
 
-export interface OllamaStatus {
+export interface ProviderStatus {
+  profileId: string;
+  provider: ProviderKind;
   isAvailable: boolean;
-  models: OllamaModel[];
+  models: ProviderModel[];
   lastChecked: string;
   errorMessage: string | null;
 }
 
+/**
+ * Generation options sent with a request or persisted as per-model defaults.
+ * Unset fields fall back to the model defaults, then to Ollama's defaults.
+ */
+export interface GenerationOptions {
+  temperature?: number;
+  topP?: number;
+  topK?: number;
+  numCtx?: number;
+  numPredict?: number;
+  repeatPenalty?: number;
+  seed?: number;
+  stop?: string[];
+}
+
 export function getOllamaConfig() {
   return invoke<OllamaConfig>("get_ollama_config");
 }
@@ -28,7 +50,54 @@ export function setOllamaConfig(config: OllamaConfig) {
 }
 
 export function getOllamaStatus() {
-  return invoke<OllamaStatus>("get_ollama_status");
+  return invoke<ProviderStatus>("get_ollama_status");
+}
+
+/** A named connection to an LLM server */
+export interface ServerProfile {
+  id: string;
+  name: string;
+  provider: ProviderKind;
+  config: OllamaConfig;
+}
+
+/** Emitted after every health check with the status of each profile, keyed by profile id */
+export const PROFILE_STATUS_EVENT = "aiMindMap://ollama/profiles/status";
+
+export function listServerProfiles() {
+  return invoke<ServerProfile[]>("list_server_profiles");
+}
+
+export function getActiveServerProfile() {
+  return invoke<ServerProfile>("get_active_server_profile");
+}
+
+export function getServerProfileStatuses() {
+  return invoke<Record<string, ProviderStatus>>("get_server_profile_statuses");
+}
+
+export function addServerProfile(name: string, provider: ProviderKind, config: OllamaConfig) {
+  return invoke<ServerProfile>("add_server_profile", { name, provider, config });
+}
+
+export function updateServerProfile(profile: ServerProfile) {
+  return invoke<void>("update_server_profile", { profile });
+}
+
+export function removeServerProfile(id: string) {
+  return invoke<void>("remove_server_profile", { id });
+}
+
+export function setActiveServerProfile(id: string) {
+  return invoke<ProviderStatus>("set_active_server_profile", { id });
+}
+
+export function getProvider() {
+  return invoke<ProviderKind>("get_provider");
+}
+
+export function setProvider(provider: ProviderKind) {
+  return invoke<void>("set_provider", { provider });
 }
 
 /**
@@ -42,10 +111,239 @@ export function getOllamaStatus() {
  * @param prompt - The prompt text to generate from
  * @returns The generated text
  */
-export function ollamaGenerate(model: string, prompt: string) {
-  return invoke<string>("ollama_generate", { model, prompt });
+export function ollamaGenerate(model: string, prompt: string, options?: GenerationOptions, requestId?: string) {
+  return invoke<string>("ollama_generate", { model, prompt, options, requestId });
+}
+
+/** A tool the model may call, `parameters` is a JSON schema object */
+export interface ToolDefinition {
+  name: string;
+  description: string;
+  parameters: Record<string, any>;
+}
+
+/**
+ * Chat and wait for the complete response
+ *
+ * Built-in tools named in `builtinTools` run in the backend. Calls to tools in
+ * `tools` come back in `toolCalls`; answer each with a "tool" message and call
+ * again to continue.
+ */
+export function ollamaChat(
+  model: string,
+  messages: ChatMessage[],
+  options?: GenerationOptions,
+  requestId?: string,
+  tools?: ToolDefinition[],
+  builtinTools?: string[]
+) {
+  return invoke<ChatMessage>("ollama_chat", { model, messages, options, requestId, tools, builtinTools });
+}
+
+/** JSON schema the response has to match, and how often to retry invalid output (default 2) */
+export interface StructuredOutput {
+  schema: Record<string, any>;
+  maxRetries?: number;
+}
+
+/** Chat and return the response parsed as JSON that matches `output.schema` */
+export function ollamaChatJson<T = unknown>(
+  model: string,
+  messages: ChatMessage[],
+  output: StructuredOutput,
+  options?: GenerationOptions,
+  requestId?: string
+) {
+  return invoke<T>("ollama_chat_json", { model, messages, output, options, requestId });
+}
+
+/** Generate a completion parsed as JSON that matches `output.schema` */
+export function ollamaGenerateJson<T = unknown>(
+  model: string,
+  prompt: string,
+  output: StructuredOutput,
+  options?: GenerationOptions,
+  requestId?: string
+) {
+  return invoke<T>("ollama_generate_json", { model, prompt, output, options, requestId });
+}
+
+export function listBuiltinTools() {
+  return invoke<ToolDefinition[]>("list_builtin_tools");
+}
+
+export function runBuiltinTool(call: ToolCall) {
+  return invoke<string>("run_builtin_tool", { call });
+}
+
+/** Load an image file as base64 with its mime type, e.g. to preview an attachment */
+export function loadImageFile(filename: string) {
+  return invoke<{ content: string; mime_type: string }>("load_image_file", { filename });
+}
+
+export function getModelOptions(model: string) {
+  return invoke<GenerationOptions>("get_model_options", { model });
+}
+
+export function getAllModelOptions() {
+  return invoke<Record<string, GenerationOptions>>("get_all_model_options");
+}
+
+export function setModelOptions(model: string, options: GenerationOptions) {
+  return invoke<void>("set_model_options", { model, options });
+}
+
+export interface GenerationStats {
+  totalDuration: number;
+  loadDuration: number;
+  promptEvalCount: number;
+  promptEvalDuration: number;
+  evalCount: number;
+  evalDuration: number;
+}
+
+export interface StreamTokenPayload {
+  requestId: string;
+  content: string;
+}
+
+export interface StreamDonePayload {
+  requestId: string;
+  message: ChatMessage;
+  stats: GenerationStats | null;
+}
+
+export interface StreamErrorPayload {
+  requestId: string;
+  error: string;
+}
+
+export const STREAM_TOKEN_EVENT = "aiMindMap://ollama/stream/token";
+export const STREAM_DONE_EVENT = "aiMindMap://ollama/stream/done";
+export const STREAM_ERROR_EVENT = "aiMindMap://ollama/stream/error";
+
+/**
+ * Chat with Ollama while streaming tokens as events
+ *
+ * Subscribe to the stream events (filtered by `requestId`) before calling this.
+ * The promise resolves with the full message once the stream is done.
+ */
+export function ollamaChatStream(requestId: string, model: string, messages: ChatMessage[], options?: GenerationOptions) {
+  return invoke<ChatMessage>("ollama_chat_stream", { requestId, model, messages, options });
+}
+
+/**
+ * Generate a completion while streaming tokens as events
+ */
+export function ollamaGenerateStream(requestId: string, model: string, prompt: string, options?: GenerationOptions) {
+  return invoke<string>("ollama_generate_stream", { requestId, model, prompt, options });
+}
+
+export type LlmRequestKind = "chat" | "chatStream" | "generate" | "generateStream" | "pull";
+
+export interface LlmRequestInfo {
+  requestId: string;
+  kind: LlmRequestKind;
+  model: string;
+  startedAt: string;
+}
+
+export const REQUEST_CANCELLED_EVENT = "aiMindMap://ollama/request/cancelled";
+
+/**
+ * Abort a running LLM request. Resolves to false if the request is not running.
+ */
+export function cancelLlmRequest(requestId: string) {
+  return invoke<boolean>("cancel_llm_request", { requestId });
+}
+
+export function listLlmRequests() {
+  return invoke<LlmRequestInfo[]>("list_llm_requests");
+}
+
+export interface PullProgressPayload {
+  requestId: string;
+  model: string;
+  status: string;
+  digest: string | null;
+  total: number | null;
+  completed: number | null;
+}
+
+export interface ModelDetails {
+  name: string;
+  family: string | null;
+  parameterCount: number | null;
+  contextLength: number | null;
+  parameters: string;
+  template: string;
+  license: string;
+  capabilities: string[];
+}
+
+export const PULL_PROGRESS_EVENT = "aiMindMap://ollama/pull/progress";
+
+/**
+ * Pull a model from the Ollama library. Progress is reported through
+ * `PULL_PROGRESS_EVENT` and the pull can be cancelled like any LLM request.
+ */
+export function pullOllamaModel(model: string, requestId?: string) {
+  return invoke<void>("pull_ollama_model", { model, requestId });
+}
+
+export function deleteOllamaModel(model: string) {
+  return invoke<void>("delete_ollama_model", { model });
+}
+
+export function copyOllamaModel(source: string, destination: string) {
+  return invoke<void>("copy_ollama_model", { source, destination });
+}
+
+export function showOllamaModel(model: string) {
+  return invoke<ModelDetails>("show_ollama_model", { model });
+}
+
+/**
+ * Embed one or more texts. Uses the configured embedding model when `model` is omitted.
+ */
+export function ollamaEmbed(input: string[], model?: string) {
+  return invoke<number[][]>("ollama_embed", { input, model });
+}
+
+export function getEmbeddingModel() {
+  return invoke<string>("get_embedding_model");
+}
+
+/**
+ * Change the embedding model. The vector index is rebuilt in the background.
+ */
+export function setEmbeddingModel(model: string) {
+  return invoke<void>("set_embedding_model", { model });
+}
+
+export interface RelatedNode {
+  fileName: string;
+  mindMapName: string;
+  nodeId: string;
+  nodeType: string;
+  preview: string;
+  score: number;
+}
+
+/**
+ * Search every indexed mind map for nodes related to a free text query
+ */
+export function findRelatedNodes(query: string, limit?: number) {
+  return invoke<RelatedNode[]>("find_related_nodes", { query, limit });
+}
+
+/**
+ * Search every indexed mind map for nodes related to an already indexed node
+ */
+export function findNodesRelatedTo(fileName: string, nodeId: string, limit?: number) {
+  return invoke<RelatedNode[]>("find_nodes_related_to", { fileName, nodeId, limit });
 }
 
-export function ollamaChat(model: string, messages: ChatMessage[]) {
-  return invoke<ChatMessage>("ollama_chat", { model, messages });
+export function rebuildVectorIndex() {
+  return invoke<number>("rebuild_vector_index");
 }
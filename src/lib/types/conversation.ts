@@ -1,6 +1,19 @@
+export interface ToolCall {
+  /** Set by OpenAI-compatible servers, echo it back in `toolCallId` */
+  id?: string
+  name: string
+  arguments: Record<string, any>
+}
 
+/** An image for vision models: a file path read by the backend, or base64 data */
+export type ImageAttachment =
+  | { path: string }
+  | { data: string, mimeType?: string }
 
 export interface ChatMessage {
   role: 'user' | 'assistant' | 'system' | 'tool' | string
   content: string
-}
\ No newline at end of file
+  images?: ImageAttachment[]
+  toolCalls?: ToolCall[]
+  toolCallId?: string
+}
@@ -1,6 +1,11 @@
 import { Edge, Node, Viewport } from "@xyflow/react";
 
+/** Per-map UI state, saved with the map without marking it unsaved */
+export type MindMapUiState = Pick<MindMap, 'viewport' | 'layoutDirection' | 'collapsedNodeIds'>;
+
 export interface MindMap {
+  /** File format version, set by the backend */
+  version?: number;
   id: number;
   name: string;
   fileName: string;
@@ -9,6 +14,10 @@ export interface MindMap {
   nodes: Node[];
   edges: Edge[];
   viewport: Viewport;
+  /** Direction new branches are laid out in */
+  layoutDirection?: string;
+  /** Nodes whose branches are collapsed */
+  collapsedNodeIds?: string[];
 
   created_at: string;
   updated_at: string;